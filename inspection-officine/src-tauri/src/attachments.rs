@@ -0,0 +1,243 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::storage;
+
+// ── Pièces jointes (photos, documents scannés) ──
+//
+// Les fichiers sont copiés dans <app_data>/attachments/, nommés par leur
+// empreinte SHA-256 : un même fichier joint deux fois n'est stocké qu'une fois.
+
+static ATTACHMENTS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Taille maximale d'une pièce jointe (25 Mo)
+pub const MAX_SIZE: usize = 25 * 1024 * 1024;
+
+/// Déclare le dossier des pièces jointes (appelé une fois au démarrage)
+pub fn init(dir: PathBuf) {
+    std::fs::create_dir_all(&dir).ok();
+    ATTACHMENTS_DIR.set(dir).ok();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: String,
+    pub inspection_id: String,
+    pub criterion_key: Option<String>,  // None = pièce jointe à l'inspection
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: u64,
+    pub sha256: String,
+    pub caption: String,
+    pub created_by: Option<String>,
+    pub created_at: String,
+}
+
+/// Description du fichier envoyé ; le contenu voyage à part, en binaire brut
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddAttachmentRequest {
+    pub inspection_id: String,
+    pub criterion_key: Option<String>,
+    pub file_name: String,
+    pub mime_type: Option<String>,  // sinon : déduit de l'extension
+    #[serde(default)]
+    pub caption: String,
+}
+
+impl AddAttachmentRequest {
+    /// Lit la description transmise en en-tête : JSON encodé par `encodeURIComponent`
+    /// (un en-tête ne peut pas contenir de caractères accentués)
+    pub fn from_header(value: &str) -> Result<Self, AppError> {
+        let json = percent_decode(value)
+            .ok_or_else(|| AppError::validation("Description de la pièce jointe mal encodée"))?;
+        serde_json::from_str(&json)
+            .map_err(|e| AppError::validation("Description de la pièce jointe invalide").with_details(e.to_string()))
+    }
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+const COLUMNS: &str = "id, inspection_id, criterion_key, file_name, mime_type, size, sha256, caption, created_by, created_at";
+
+fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Attachment> {
+    Ok(Attachment {
+        id: row.get(0)?, inspection_id: row.get(1)?, criterion_key: row.get(2)?,
+        file_name: row.get(3)?, mime_type: row.get(4)?, size: row.get(5)?, sha256: row.get(6)?,
+        caption: row.get::<_,String>(7).unwrap_or_default(), created_by: row.get(8)?, created_at: row.get(9)?,
+    })
+}
+
+fn dir() -> Result<&'static PathBuf, AppError> {
+    ATTACHMENTS_DIR.get().ok_or_else(|| AppError::internal("Dossier des pièces jointes non initialisé"))
+}
+
+/// Emplacement d'un fichier : <dossier>/<2 premiers caractères>/<empreinte>
+fn file_path(sha256: &str) -> Result<PathBuf, AppError> {
+    Ok(dir()?.join(&sha256[..2]).join(sha256))
+}
+
+fn hash(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn guess_mime(file_name: &str) -> &'static str {
+    let ext = file_name.rsplit('.').next().unwrap_or("").to_lowercase();
+    match ext.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        "gif" => "image/gif",
+        "pdf" => "application/pdf",
+        "tif" | "tiff" => "image/tiff",
+        _ => "application/octet-stream",
+    }
+}
+
+// ── Ajouter ──
+
+pub fn add_attachment(db: &Database, req: &AddAttachmentRequest, data: &[u8], user_id: &str) -> Result<Attachment, AppError> {
+    if data.is_empty() {
+        return Err(AppError::validation("Fichier vide"));
+    }
+    if data.len() > MAX_SIZE {
+        return Err(AppError::validation(format!("Fichier trop volumineux (maximum {} Mo)", MAX_SIZE / 1024 / 1024)));
+    }
+    let file_name = std::path::Path::new(req.file_name.trim())
+        .file_name().and_then(|n| n.to_str()).unwrap_or("").to_string();
+    if file_name.is_empty() {
+        return Err(AppError::validation("Nom de fichier manquant"));
+    }
+
+    let conn = db.conn.lock()?;
+    if let Some(ref key) = req.criterion_key {
+        storage::check_criterion(&conn, &req.inspection_id, key)?;
+    }
+    storage::ensure_editable(&conn, &req.inspection_id)?;
+
+    // Copie du fichier (écriture atomique, ignorée si le contenu est déjà stocké)
+    let sha256 = hash(data);
+    let path = file_path(&sha256)?;
+    let created = !path.exists();
+    if created {
+        let parent = path.parent().ok_or_else(|| AppError::internal("Chemin de pièce jointe invalide"))?;
+        std::fs::create_dir_all(parent).map_err(|e| AppError::io("Erreur écriture pièce jointe", e))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, data).map_err(|e| AppError::io("Erreur écriture pièce jointe", e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| AppError::io("Erreur écriture pièce jointe", e))?;
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let mime_type = req.mime_type.clone().filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| guess_mime(&file_name).to_string());
+    conn.execute(
+        "INSERT INTO attachments (id, inspection_id, criterion_key, file_name, mime_type, size, sha256, caption, created_by)
+         VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9)",
+        params![id, req.inspection_id, req.criterion_key, file_name, mime_type, data.len() as u64,
+            sha256, req.caption.trim(), user_id],
+    ).map_err(|e| {
+        // Pas de fichier orphelin : on retire la copie qui vient d'être faite
+        if created {
+            std::fs::remove_file(&path).ok();
+        }
+        AppError::database("Erreur enregistrement pièce jointe", e)
+    })?;
+    get_by_id(&conn, &id)
+}
+
+// ── Lister / récupérer ──
+
+fn get_by_id(conn: &Connection, attachment_id: &str) -> Result<Attachment, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM attachments WHERE id = ?1", COLUMNS),
+        params![attachment_id], from_row,
+    ).map_err(AppError::missing("Pièce jointe non trouvée"))
+}
+
+/// Pièces jointes d'une inspection, éventuellement limitées à un critère
+pub fn list_attachments(db: &Database, inspection_id: &str, criterion_key: Option<&str>) -> Result<Vec<Attachment>, AppError> {
+    let conn = db.conn.lock()?;
+    let mut sql = format!("SELECT {} FROM attachments WHERE inspection_id = ?1", COLUMNS);
+    let mut bind_values: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(inspection_id.to_string())];
+    if let Some(key) = criterion_key {
+        sql.push_str(" AND criterion_key = ?2");
+        bind_values.push(Box::new(key.to_string()));
+    }
+    sql.push_str(" ORDER BY created_at");
+
+    let refs: Vec<&dyn rusqlite::types::ToSql> = bind_values.iter().map(|b| b.as_ref()).collect();
+    let mut stmt = conn.prepare(&sql)?;
+    let list = stmt.query_map(refs.as_slice(), from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(list)
+}
+
+/// Contenu d'une pièce jointe, après contrôle de son empreinte
+pub fn get_attachment(db: &Database, attachment_id: &str) -> Result<Vec<u8>, AppError> {
+    let attachment = {
+        let conn = db.conn.lock()?;
+        get_by_id(&conn, attachment_id)?
+    };
+    let data = std::fs::read(file_path(&attachment.sha256)?)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => AppError::not_found("Fichier de la pièce jointe introuvable").with_details(e.to_string()),
+            _ => AppError::io("Erreur lecture pièce jointe", e),
+        })?;
+    if hash(&data) != attachment.sha256 {
+        return Err(AppError::internal("Pièce jointe altérée (empreinte différente)"));
+    }
+    Ok(data)
+}
+
+// ── Supprimer ──
+
+pub fn delete_attachment(db: &Database, attachment_id: &str) -> Result<Attachment, AppError> {
+    let conn = db.conn.lock()?;
+    let attachment = get_by_id(&conn, attachment_id)?;
+    storage::ensure_editable(&conn, &attachment.inspection_id)?;
+    conn.execute("DELETE FROM attachments WHERE id = ?1", params![attachment_id])?;
+    remove_unreferenced(&conn, std::slice::from_ref(&attachment.sha256));
+    Ok(attachment)
+}
+
+/// Empreintes des fichiers joints à une inspection (avant sa suppression)
+pub fn hashes_for_inspection(conn: &Connection, inspection_id: &str) -> Vec<String> {
+    conn.prepare("SELECT DISTINCT sha256 FROM attachments WHERE inspection_id = ?1")
+        .and_then(|mut stmt| stmt.query_map(params![inspection_id], |r| r.get(0))?.collect())
+        .unwrap_or_default()
+}
+
+/// Supprime du disque les fichiers qui ne sont plus référencés
+pub fn remove_unreferenced(conn: &Connection, hashes: &[String]) {
+    for sha256 in hashes {
+        let used: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM attachments WHERE sha256 = ?1",
+            params![sha256], |r| r.get(0)
+        ).unwrap_or(true);
+        if !used {
+            if let Ok(path) = file_path(sha256) {
+                std::fs::remove_file(path).ok();
+            }
+        }
+    }
+}
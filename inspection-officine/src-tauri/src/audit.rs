@@ -1,6 +1,7 @@
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use crate::db::Database;
+use crate::error::AppError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditEntry {
@@ -67,8 +68,8 @@ pub fn log_user_action(
 }
 
 /// Requêter le journal d'audit avec filtres
-pub fn query_audit(db: &Database, filter: &AuditFilter) -> Result<Vec<AuditEntry>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub fn query_audit(db: &Database, filter: &AuditFilter) -> Result<Vec<AuditEntry>, AppError> {
+    let conn = db.conn.lock()?;
 
     let mut sql = String::from(
         "SELECT id, timestamp, user_id, username, action, entity_type, entity_id, details FROM audit_log WHERE 1=1"
@@ -117,7 +118,7 @@ pub fn query_audit(db: &Database, filter: &AuditFilter) -> Result<Vec<AuditEntry
 
     let refs: Vec<&dyn rusqlite::types::ToSql> = bind_values.iter().map(|b| b.as_ref()).collect();
 
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&sql)?;
     let entries = stmt.query_map(refs.as_slice(), |row| {
         Ok(AuditEntry {
             id: row.get(0)?,
@@ -129,7 +130,7 @@ pub fn query_audit(db: &Database, filter: &AuditFilter) -> Result<Vec<AuditEntry
             entity_id: row.get(6)?,
             details: row.get(7)?,
         })
-    }).map_err(|e| e.to_string())?
+    })?
     .filter_map(|r| r.ok())
     .collect();
 
@@ -137,8 +138,8 @@ pub fn query_audit(db: &Database, filter: &AuditFilter) -> Result<Vec<AuditEntry
 }
 
 /// Compter le total d'entrées (pour pagination)
-pub fn count_audit(db: &Database, filter: &AuditFilter) -> Result<u32, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub fn count_audit(db: &Database, filter: &AuditFilter) -> Result<u32, AppError> {
+    let conn = db.conn.lock()?;
     let mut sql = String::from("SELECT COUNT(*) FROM audit_log WHERE 1=1");
     let mut bind_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
     let mut idx = 1;
@@ -169,5 +170,5 @@ pub fn count_audit(db: &Database, filter: &AuditFilter) -> Result<u32, String> {
     }
 
     let refs: Vec<&dyn rusqlite::types::ToSql> = bind_values.iter().map(|b| b.as_ref()).collect();
-    conn.query_row(&sql, refs.as_slice(), |r| r.get(0)).map_err(|e| e.to_string())
+    conn.query_row(&sql, refs.as_slice(), |r| r.get(0)).map_err(AppError::from)
 }
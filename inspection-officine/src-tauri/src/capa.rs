@@ -0,0 +1,264 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::storage;
+
+// ── Actions correctives (CAPA) sur les écarts ──
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionStatus {
+    Open,
+    InProgress,
+    Closed,
+    Verified,
+}
+
+impl ActionStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActionStatus::Open => "open",
+            ActionStatus::InProgress => "in_progress",
+            ActionStatus::Closed => "closed",
+            ActionStatus::Verified => "verified",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ActionStatus::Open => "Ouverte",
+            ActionStatus::InProgress => "En cours",
+            ActionStatus::Closed => "Clôturée",
+            ActionStatus::Verified => "Vérifiée",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "open" => Ok(ActionStatus::Open),
+            "in_progress" => Ok(ActionStatus::InProgress),
+            "closed" => Ok(ActionStatus::Closed),
+            "verified" => Ok(ActionStatus::Verified),
+            _ => Err(AppError::validation(format!("Statut d'action inconnu : {}", value))),
+        }
+    }
+
+    /// Rôles autorisés pour passer de `self` à `to` (None = transition interdite).
+    /// La vérification, ou son refus (retour en cours), revient aux chefs.
+    pub fn transition_roles(self, to: ActionStatus) -> Option<&'static [&'static str]> {
+        use ActionStatus::*;
+        match (self, to) {
+            (Open, InProgress) | (Open, Closed) | (InProgress, Closed) => Some(&["admin", "lead_inspector", "inspector"]),
+            (Closed, Verified) | (Closed, InProgress) => Some(&["admin", "lead_inspector"]),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrectiveAction {
+    pub id: String,
+    pub inspection_id: String,
+    pub criterion_key: String,
+    pub establishment_id: Option<String>,
+    pub establishment: Option<String>,
+    pub description: String,
+    pub responsible: String,
+    pub due_date: Option<String>,
+    pub status: ActionStatus,
+    pub closure_evidence: Option<String>,
+    pub closed_at: Option<String>,
+    pub verified_by: Option<String>,
+    pub verified_at: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateActionRequest {
+    pub inspection_id: String,
+    pub criterion_key: String,
+    pub description: String,
+    pub responsible: String,
+    pub due_date: Option<String>,  // YYYY-MM-DD
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateActionRequest {
+    pub description: Option<String>,
+    pub responsible: Option<String>,
+    /// Absent : inchangée ; `null` : échéance retirée
+    #[serde(default, deserialize_with = "present")]
+    pub due_date: Option<Option<String>>,
+}
+
+/// Distingue un champ absent (`None`) d'un champ `null` (`Some(None)`)
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where D: serde::Deserializer<'de>, T: Deserialize<'de> {
+    T::deserialize(deserializer).map(Some)
+}
+
+const SELECT: &str = "SELECT a.id, a.inspection_id, a.criterion_key, i.establishment_id, i.establishment,
+        a.description, a.responsible, a.due_date, a.status, a.closure_evidence, a.closed_at,
+        a.verified_by, a.verified_at, a.created_by, a.created_at, a.updated_at
+    FROM corrective_actions a JOIN inspections i ON a.inspection_id = i.id";
+
+fn from_row(row: &rusqlite::Row) -> rusqlite::Result<CorrectiveAction> {
+    let status: String = row.get(8)?;
+    Ok(CorrectiveAction {
+        id: row.get(0)?, inspection_id: row.get(1)?, criterion_key: row.get(2)?,
+        establishment_id: row.get(3)?, establishment: row.get(4)?,
+        description: row.get(5)?, responsible: row.get::<_,String>(6).unwrap_or_default(),
+        due_date: row.get(7)?, status: ActionStatus::parse(&status).unwrap_or(ActionStatus::Open),
+        closure_evidence: row.get(9)?, closed_at: row.get(10)?,
+        verified_by: row.get(11)?, verified_at: row.get(12)?,
+        created_by: row.get(13)?, created_at: row.get(14)?, updated_at: row.get(15)?,
+    })
+}
+
+fn validate_due_date(due_date: Option<&str>) -> Result<(), AppError> {
+    match due_date {
+        Some(d) if chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").is_err() =>
+            Err(AppError::validation(format!("Échéance invalide (AAAA-MM-JJ attendu) : {}", d))),
+        _ => Ok(()),
+    }
+}
+
+fn get_by_id(conn: &Connection, action_id: &str) -> Result<CorrectiveAction, AppError> {
+    conn.query_row(&format!("{} WHERE a.id = ?1", SELECT), params![action_id], from_row)
+        .map_err(AppError::missing("Action corrective non trouvée"))
+}
+
+pub fn get_action(db: &Database, action_id: &str) -> Result<CorrectiveAction, AppError> {
+    let conn = db.conn.lock()?;
+    get_by_id(&conn, action_id)
+}
+
+// ── Créer ──
+
+/// Une action corrective ne peut porter que sur une réponse non conforme
+pub fn create_action(db: &Database, req: &CreateActionRequest, user_id: &str) -> Result<CorrectiveAction, AppError> {
+    if req.description.trim().is_empty() {
+        return Err(AppError::validation("La description de l'action est obligatoire"));
+    }
+    validate_due_date(req.due_date.as_deref())?;
+    let conn = db.conn.lock()?;
+    storage::check_criterion(&conn, &req.inspection_id, &req.criterion_key)?;
+
+    let non_conforme: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM responses WHERE inspection_id = ?1 AND criterion_key = ?2 AND conforme = 0",
+        params![req.inspection_id, req.criterion_key], |r| r.get(0)
+    )?;
+    if !non_conforme {
+        return Err(AppError::validation("Aucun écart enregistré pour ce critère"));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO corrective_actions (id, inspection_id, criterion_key, description, responsible, due_date, created_by)
+         VALUES (?1,?2,?3,?4,?5,?6,?7)",
+        params![id, req.inspection_id, req.criterion_key, req.description.trim(), req.responsible.trim(), req.due_date, user_id],
+    ).map_err(|e| AppError::database("Erreur création action", e))?;
+    get_by_id(&conn, &id)
+}
+
+// ── Modifier ──
+
+pub fn update_action(db: &Database, action_id: &str, req: &UpdateActionRequest) -> Result<(), AppError> {
+    validate_due_date(req.due_date.as_ref().and_then(|d| d.as_deref()))?;
+    let description = req.description.as_deref().map(str::trim);
+    if description == Some("") {
+        return Err(AppError::validation("La description de l'action est obligatoire"));
+    }
+    let conn = db.conn.lock()?;
+    let action = get_by_id(&conn, action_id)?;
+    if action.status == ActionStatus::Verified {
+        return Err(AppError::locked("Action vérifiée : modification impossible"));
+    }
+    // Une seule requête : la modification est appliquée entièrement ou pas du tout
+    conn.execute(
+        "UPDATE corrective_actions SET description=COALESCE(?1, description), responsible=COALESCE(?2, responsible),
+         due_date=CASE WHEN ?3 THEN ?4 ELSE due_date END, updated_at=datetime('now','localtime') WHERE id=?5",
+        params![description, req.responsible.as_deref().map(str::trim), req.due_date.is_some(),
+            req.due_date.clone().flatten(), action_id],
+    )?;
+    Ok(())
+}
+
+// ── Changer le statut ──
+
+/// La clôture exige une preuve (description du justificatif reçu) ;
+/// le contrôle des rôles est fait par l'appelant via `transition_roles`.
+pub fn set_action_status(db: &Database, action_id: &str, from: ActionStatus, to: ActionStatus,
+    evidence: Option<&str>, user_id: &str) -> Result<(), AppError> {
+    if from.transition_roles(to).is_none() {
+        return Err(AppError::forbidden(format!("Transition interdite : {} → {}", from.label(), to.label())));
+    }
+    let evidence = evidence.map(str::trim).filter(|e| !e.is_empty());
+    if to == ActionStatus::Closed && evidence.is_none() {
+        return Err(AppError::validation("Preuve de clôture obligatoire"));
+    }
+
+    let conn = db.conn.lock()?;
+    let updated = match to {
+        ActionStatus::Closed => conn.execute(
+            "UPDATE corrective_actions SET status=?1, closure_evidence=?2, closed_at=datetime('now','localtime'),
+             updated_at=datetime('now','localtime') WHERE id=?3 AND status=?4",
+            params![to.as_str(), evidence, action_id, from.as_str()],
+        ),
+        ActionStatus::Verified => conn.execute(
+            "UPDATE corrective_actions SET status=?1, verified_by=?2, verified_at=datetime('now','localtime'),
+             updated_at=datetime('now','localtime') WHERE id=?3 AND status=?4",
+            params![to.as_str(), user_id, action_id, from.as_str()],
+        ),
+        // Réouverture d'une action clôturée : la preuve refusée ne doit plus
+        // apparaître comme preuve de clôture
+        ActionStatus::InProgress => conn.execute(
+            "UPDATE corrective_actions SET status=?1, closure_evidence=NULL, closed_at=NULL,
+             updated_at=datetime('now','localtime') WHERE id=?2 AND status=?3",
+            params![to.as_str(), action_id, from.as_str()],
+        ),
+        _ => conn.execute(
+            "UPDATE corrective_actions SET status=?1, updated_at=datetime('now','localtime') WHERE id=?2 AND status=?3",
+            params![to.as_str(), action_id, from.as_str()],
+        ),
+    }?;
+    if updated == 0 {
+        return Err(AppError::conflict("Le statut de l'action a été modifié entre-temps"));
+    }
+    Ok(())
+}
+
+// ── Lister ──
+
+pub fn list_actions(db: &Database, inspection_id: &str) -> Result<Vec<CorrectiveAction>, AppError> {
+    let conn = db.conn.lock()?;
+    let mut stmt = conn.prepare(&format!("{} WHERE a.inspection_id = ?1 ORDER BY a.created_at", SELECT))?;
+    let list = stmt.query_map(params![inspection_id], from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(list)
+}
+
+/// Actions ouvertes ou en cours dont l'échéance est dépassée
+pub fn list_overdue(db: &Database, establishment_id: Option<&str>) -> Result<Vec<CorrectiveAction>, AppError> {
+    let conn = db.conn.lock()?;
+    let mut sql = format!(
+        "{} WHERE a.status IN ('open','in_progress') AND a.due_date IS NOT NULL
+            AND a.due_date < date('now','localtime')", SELECT);
+    let mut bind_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    if let Some(eid) = establishment_id {
+        sql.push_str(" AND i.establishment_id = ?1");
+        bind_values.push(Box::new(eid.to_string()));
+    }
+    sql.push_str(" ORDER BY i.establishment, a.due_date");
+
+    let refs: Vec<&dyn rusqlite::types::ToSql> = bind_values.iter().map(|b| b.as_ref()).collect();
+    let mut stmt = conn.prepare(&sql)?;
+    let list = stmt.query_map(refs.as_slice(), from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(list)
+}
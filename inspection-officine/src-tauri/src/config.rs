@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+// ── Configuration ──
+//
+// Lue au démarrage dans <app_data>/config.toml. Fichier et clés sont
+// facultatifs : toute valeur absente garde sa valeur par défaut.
+//
+//   [password]
+//   min_length = 12
+//   require_symbol = true
+//   history = 5            # derniers mots de passe non réutilisables
+//   max_age_days = 180     # 0 = pas d'expiration
+//
+//   [security]
+//   bcrypt_cost = 12
+//
+//   [session]
+//   idle_minutes = 30      # déconnexion après inactivité
+//   max_hours = 12         # durée maximale d'une session, activité ou non
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub password: PasswordPolicy,
+    pub security: SecurityConfig,
+    pub session: SessionConfig,
+}
+
+/// Règles imposées à tout nouveau mot de passe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_upper: bool,
+    pub require_lower: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub history: u32,
+    pub max_age_days: u32,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: 10, require_upper: true, require_lower: true, require_digit: true,
+            require_symbol: false, history: 5, max_age_days: 180,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityConfig {
+    pub bcrypt_cost: u32,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        SecurityConfig { bcrypt_cost: 10 }
+    }
+}
+
+/// Durée de vie des sessions : l'expiration par inactivité est repoussée à
+/// chaque requête, sans jamais dépasser la durée maximale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    pub idle_minutes: u32,
+    pub max_hours: u32,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig { idle_minutes: 30, max_hours: 12 }
+    }
+}
+
+/// Charge la configuration (appelé une fois au démarrage). Un fichier
+/// illisible ou invalide est ignoré : l'erreur est renvoyée pour le journal.
+pub fn init(path: &Path) -> Option<String> {
+    let (config, error) = match std::fs::read_to_string(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (Config::default(), None),
+        Err(e) => (Config::default(), Some(format!("{} : {}", path.display(), e))),
+        Ok(text) => match toml::from_str::<Config>(&text) {
+            Ok(config) => match config.validate() {
+                Ok(()) => (config, None),
+                Err(e) => (Config::default(), Some(format!("{} : {}", path.display(), e))),
+            },
+            Err(e) => (Config::default(), Some(format!("{} : {}", path.display(), e))),
+        },
+    };
+    CONFIG.set(config).ok();
+    error
+}
+
+/// Configuration en vigueur (valeurs par défaut si `init` n'a pas été appelé)
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+impl Config {
+    fn validate(&self) -> Result<(), String> {
+        if !(10..=16).contains(&self.security.bcrypt_cost) {
+            return Err(format!("bcrypt_cost hors limites (10 à 16) : {}", self.security.bcrypt_cost));
+        }
+        if self.password.min_length == 0 {
+            return Err("min_length doit être au moins 1".to_string());
+        }
+        if self.session.idle_minutes == 0 || self.session.max_hours == 0 {
+            return Err("idle_minutes et max_hours doivent être au moins 1".to_string());
+        }
+        Ok(())
+    }
+}
@@ -1,108 +1,762 @@
-use rusqlite::{Connection, params};
-use std::path::PathBuf;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use crate::error::{AppError, ErrorCode};
 
 pub struct Database {
     pub conn: Mutex<Connection>,
+    /// Clé des empreintes de jetons de session, conservée hors de la base
+    pub session_key: [u8; 32],
 }
 
 impl Database {
-    pub fn new(app_dir: PathBuf) -> Self {
-        std::fs::create_dir_all(&app_dir).ok();
+    /// Ouvre la base et applique les migrations en attente. Une erreur
+    /// laisse la base au dernier schéma complet (étapes transactionnelles).
+    pub fn new(app_dir: PathBuf) -> Result<Self, AppError> {
+        std::fs::create_dir_all(&app_dir)
+            .map_err(|e| AppError::io("Impossible de créer le dossier de l'application", e))?;
         let db_path = app_dir.join("inspections.db");
         let conn = Connection::open(&db_path)
-            .expect("Impossible d'ouvrir la base de données");
+            .map_err(|e| db_error("Impossible d'ouvrir la base de données", e))?;
 
         conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;").ok();
+        migrate(&conn, &app_dir.join("backups"))?;
+        let session_key = load_session_key(&app_dir.join("session.key"))?;
+        Ok(Database { conn: Mutex::new(conn), session_key })
+    }
+}
 
-        conn.execute_batch("
-            -- Utilisateurs
-            CREATE TABLE IF NOT EXISTS users (
-                id          TEXT PRIMARY KEY,
-                username    TEXT NOT NULL UNIQUE,
-                full_name   TEXT NOT NULL,
-                role        TEXT NOT NULL DEFAULT 'inspector'
-                            CHECK(role IN ('admin','lead_inspector','inspector','viewer')),
-                password_hash TEXT NOT NULL,
-                active      INTEGER NOT NULL DEFAULT 1,
-                created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
-                updated_at  TEXT NOT NULL DEFAULT (datetime('now','localtime'))
-            );
-
-            -- Sessions (token simple)
-            CREATE TABLE IF NOT EXISTS sessions (
-                token       TEXT PRIMARY KEY,
-                user_id     TEXT NOT NULL REFERENCES users(id),
-                created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
-                expires_at  TEXT NOT NULL
-            );
-
-            -- Inspections
-            CREATE TABLE IF NOT EXISTS inspections (
-                id          TEXT PRIMARY KEY,
-                grid_id     TEXT NOT NULL,
-                status      TEXT NOT NULL DEFAULT 'draft'
-                            CHECK(status IN ('draft','in_progress','completed','validated','archived')),
-                date_inspection TEXT,
-                establishment   TEXT,
-                inspection_type TEXT,
-                inspectors      TEXT,  -- JSON array
-                created_by  TEXT REFERENCES users(id),
-                validated_by TEXT REFERENCES users(id),
-                validated_at TEXT,
-                created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
-                updated_at  TEXT NOT NULL DEFAULT (datetime('now','localtime'))
-            );
-
-            -- Réponses
-            CREATE TABLE IF NOT EXISTS responses (
-                id              INTEGER PRIMARY KEY AUTOINCREMENT,
-                inspection_id   TEXT NOT NULL REFERENCES inspections(id) ON DELETE CASCADE,
-                criterion_id    INTEGER NOT NULL,
-                conforme        INTEGER,  -- NULL=non répondu, 0=non conforme, 1=conforme
-                observation     TEXT DEFAULT '',
-                updated_by      TEXT REFERENCES users(id),
-                updated_at      TEXT NOT NULL DEFAULT (datetime('now','localtime')),
-                UNIQUE(inspection_id, criterion_id)
-            );
-
-            -- Audit trail
-            CREATE TABLE IF NOT EXISTS audit_log (
-                id          INTEGER PRIMARY KEY AUTOINCREMENT,
-                timestamp   TEXT NOT NULL DEFAULT (datetime('now','localtime')),
-                user_id     TEXT REFERENCES users(id),
-                username    TEXT,
-                action      TEXT NOT NULL,
-                entity_type TEXT,  -- 'inspection','response','user','session'
-                entity_id   TEXT,
-                details     TEXT,  -- JSON libre
-                ip_info     TEXT
-            );
-
-            -- Index pour performance
-            CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit_log(timestamp);
-            CREATE INDEX IF NOT EXISTS idx_audit_user ON audit_log(user_id);
-            CREATE INDEX IF NOT EXISTS idx_audit_entity ON audit_log(entity_type, entity_id);
-            CREATE INDEX IF NOT EXISTS idx_responses_insp ON responses(inspection_id);
-            CREATE INDEX IF NOT EXISTS idx_inspections_status ON inspections(status);
-            CREATE INDEX IF NOT EXISTS idx_inspections_user ON inspections(created_by);
-        ").expect("Erreur création tables");
-
-        // Créer l'admin par défaut s'il n'existe pas
-        let admin_exists: bool = conn.query_row(
-            "SELECT COUNT(*) > 0 FROM users WHERE username = 'admin'",
-            [], |r| r.get(0)
-        ).unwrap_or(false);
-
-        if !admin_exists {
-            let hash = bcrypt::hash("admin123", 8).unwrap();
-            let id = uuid::Uuid::new_v4().to_string();
+#[cfg(test)]
+impl Database {
+    /// Base neuve dans un dossier temporaire (tests des autres modules)
+    pub fn open_temp() -> Self {
+        let dir = std::env::temp_dir().join(format!("inspection-officine-test-{}", uuid::Uuid::new_v4()));
+        Database::new(dir).expect("base de test")
+    }
+}
+
+fn parse_key(text: &str) -> Option<[u8; 32]> {
+    let text = text.trim();
+    if text.len() != 64 || !text.is_ascii() {
+        return None;
+    }
+    let mut key = [0u8; 32];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(key)
+}
+
+/// Lit la clé de session (hexadécimal), ou la crée au premier lancement.
+/// Une clé perdue ou régénérée invalide simplement les sessions ouvertes.
+fn load_session_key(path: &Path) -> Result<[u8; 32], AppError> {
+    if let Some(key) = std::fs::read_to_string(path).ok().and_then(|text| parse_key(&text)) {
+        return Ok(key);
+    }
+    let mut key = [0u8; 32];
+    getrandom::getrandom(&mut key)
+        .map_err(|e| AppError::internal("Impossible de créer la clé de session").with_details(e.to_string()))?;
+    let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)
+        .map_err(|e| AppError::io("Impossible de créer la clé de session", e))?;
+    std::io::Write::write_all(&mut file, hex.as_bytes())
+        .map_err(|e| AppError::io("Impossible de créer la clé de session", e))?;
+    Ok(key)
+}
+
+// ── Migrations ──
+
+struct Migration {
+    version: u32,
+    name: &'static str,
+    up: fn(&Connection) -> rusqlite::Result<()>,
+}
+
+/// Étapes ordonnées ; `PRAGMA user_version` = dernière étape appliquée.
+/// Ne jamais modifier une étape publiée : en ajouter une nouvelle.
+/// Les étapes 2 à 7 reprennent les conversions des bases antérieures à la
+/// numérotation, d'où la détection des colonnes existantes.
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "schéma initial", up: create_schema },
+    Migration { version: 2, name: "clés stables des critères", up: migrate_response_keys },
+    Migration { version: 3, name: "versions de grille", up: migrate_grid_versions },
+    Migration { version: 4, name: "réponses non applicables", up: migrate_not_applicable },
+    Migration { version: 5, name: "registre des établissements", up: migrate_establishments },
+    Migration { version: 6, name: "contre-visites", up: migrate_follow_ups },
+    Migration { version: 7, name: "historique des réponses", up: migrate_response_revisions },
+    Migration { version: 8, name: "changement de mot de passe imposé", up: migrate_must_change_password },
+    Migration { version: 9, name: "échecs de connexion", up: create_login_attempts },
+    Migration { version: 10, name: "historique des mots de passe", up: create_password_history },
+    Migration { version: 11, name: "sessions révocables", up: migrate_sessions },
+    Migration { version: 12, name: "empreintes des jetons de session", up: hash_session_tokens },
+];
+
+fn db_error(message: impl Into<String>, e: rusqlite::Error) -> AppError {
+    AppError::new(ErrorCode::Database, message).with_details(e.to_string())
+}
+
+/// Applique les étapes postérieures à `user_version`, chacune dans sa
+/// transaction, après une copie de sauvegarde de la base existante.
+fn migrate(conn: &Connection, backup_dir: &Path) -> Result<(), AppError> {
+    let current: u32 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))
+        .map_err(|e| db_error("Impossible de lire la version du schéma", e))?;
+    let latest = MIGRATIONS.last().map_or(0, |m| m.version);
+    if current > latest {
+        return Err(AppError::new(ErrorCode::Database, format!(
+            "Base de données créée par une version plus récente de l'application (schéma {}, maximum pris en charge : {})",
+            current, latest)));
+    }
+    if current == latest {
+        return Ok(());
+    }
+
+    let has_tables: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table'", [], |r| r.get(0)
+    ).map_err(|e| db_error("Impossible de lire le schéma", e))?;
+    let backup = if has_tables { Some(backup(conn, backup_dir, current)?) } else { None };
+
+    for m in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| db_error("Impossible de démarrer la migration", e))?;
+        (m.up)(&tx)
+            .and_then(|_| tx.pragma_update(None, "user_version", m.version))
+            .and_then(|_| tx.commit())
+            .map_err(|e| {
+                let mut message = format!("Échec de la migration {} ({}) : la base reste au schéma {}",
+                    m.version, m.name, m.version - 1);
+                if let Some(ref path) = backup {
+                    message.push_str(&format!(", sauvegarde : {}", path.display()));
+                }
+                db_error(message, e)
+            })?;
+    }
+    Ok(())
+}
+
+/// Copie cohérente de la base (WAL compris) avant migration
+fn backup(conn: &Connection, backup_dir: &Path, version: u32) -> Result<PathBuf, AppError> {
+    std::fs::create_dir_all(backup_dir)
+        .map_err(|e| AppError::io("Impossible de créer le dossier des sauvegardes", e))?;
+    let path = backup_dir.join(format!("inspections-v{}-{}.db",
+        version, chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    conn.execute("VACUUM INTO ?1", params![path.to_string_lossy()])
+        .map_err(|e| db_error("Sauvegarde avant migration impossible, base non modifiée", e))?;
+    Ok(path)
+}
+
+fn create_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(SCHEMA)
+}
+
+const SCHEMA: &str = "
+    -- Utilisateurs
+    CREATE TABLE IF NOT EXISTS users (
+        id          TEXT PRIMARY KEY,
+        username    TEXT NOT NULL UNIQUE,
+        full_name   TEXT NOT NULL,
+        role        TEXT NOT NULL DEFAULT 'inspector'
+                    CHECK(role IN ('admin','lead_inspector','inspector','viewer')),
+        password_hash TEXT NOT NULL,
+        active      INTEGER NOT NULL DEFAULT 1,
+        created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+        updated_at  TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+    );
+
+    -- Sessions (token simple)
+    CREATE TABLE IF NOT EXISTS sessions (
+        token       TEXT PRIMARY KEY,
+        user_id     TEXT NOT NULL REFERENCES users(id),
+        created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+        expires_at  TEXT NOT NULL
+    );
+
+    -- Registre des établissements
+    CREATE TABLE IF NOT EXISTS establishments (
+        id              TEXT PRIMARY KEY,
+        name            TEXT NOT NULL,
+        name_key        TEXT NOT NULL,  -- nom normalisé (dédoublonnage, recherche)
+        establishment_type TEXT NOT NULL DEFAULT 'officine',
+        licence_number  TEXT,
+        owner_pharmacist TEXT,
+        address         TEXT,
+        commune         TEXT,
+        departement     TEXT,
+        zone_sanitaire  TEXT,
+        latitude        REAL,
+        longitude       REAL,
+        active          INTEGER NOT NULL DEFAULT 1,
+        created_at      TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+        updated_at      TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+    );
+    CREATE INDEX IF NOT EXISTS idx_establishments_key ON establishments(name_key);
+
+    -- Inspections
+    CREATE TABLE IF NOT EXISTS inspections (
+        id          TEXT PRIMARY KEY,
+        grid_id     TEXT NOT NULL,
+        grid_version TEXT,     -- version de la grille utilisée à la création
+        status      TEXT NOT NULL DEFAULT 'draft'
+                    CHECK(status IN ('draft','in_progress','completed','validated','archived')),
+        date_inspection TEXT,
+        establishment   TEXT,  -- nom affiché (copie du registre)
+        establishment_id TEXT REFERENCES establishments(id),
+        inspection_type TEXT,
+        parent_id       TEXT REFERENCES inspections(id),  -- contre-visite : inspection d'origine
+        criteria_scope  TEXT,  -- contre-visite : JSON array des clés reprises
+        inspectors      TEXT,  -- JSON array
+        created_by  TEXT REFERENCES users(id),
+        validated_by TEXT REFERENCES users(id),
+        validated_at TEXT,
+        created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+        updated_at  TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+    );
+
+    -- Réponses
+    CREATE TABLE IF NOT EXISTS responses (
+        id              INTEGER PRIMARY KEY AUTOINCREMENT,
+        inspection_id   TEXT NOT NULL REFERENCES inspections(id) ON DELETE CASCADE,
+        criterion_key   TEXT NOT NULL,  -- clé stable du critère (Criterion.key)
+        conforme        INTEGER,  -- NULL=non répondu, 0=non conforme, 1=conforme
+        not_applicable  INTEGER NOT NULL DEFAULT 0,  -- 1 = N/A (conforme NULL, observation = justification)
+        observation     TEXT DEFAULT '',
+        updated_by      TEXT REFERENCES users(id),
+        updated_at      TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+        UNIQUE(inspection_id, criterion_key)
+    );
+
+    -- Historique des réponses (une ligne par état enregistré)
+    CREATE TABLE IF NOT EXISTS response_revisions (
+        id              INTEGER PRIMARY KEY AUTOINCREMENT,
+        inspection_id   TEXT NOT NULL REFERENCES inspections(id) ON DELETE CASCADE,
+        criterion_key   TEXT NOT NULL,
+        revision        INTEGER NOT NULL,
+        conforme        INTEGER,
+        not_applicable  INTEGER NOT NULL DEFAULT 0,
+        observation     TEXT DEFAULT '',
+        changed_by      TEXT REFERENCES users(id),
+        changed_at      TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+        UNIQUE(inspection_id, criterion_key, revision)
+    );
+
+    -- Actions correctives (CAPA) sur les écarts
+    CREATE TABLE IF NOT EXISTS corrective_actions (
+        id              TEXT PRIMARY KEY,
+        inspection_id   TEXT NOT NULL REFERENCES inspections(id) ON DELETE CASCADE,
+        criterion_key   TEXT NOT NULL,
+        description     TEXT NOT NULL,
+        responsible     TEXT NOT NULL DEFAULT '',
+        due_date        TEXT,  -- YYYY-MM-DD
+        status          TEXT NOT NULL DEFAULT 'open'
+                        CHECK(status IN ('open','in_progress','closed','verified')),
+        closure_evidence TEXT,
+        closed_at       TEXT,
+        verified_by     TEXT REFERENCES users(id),
+        verified_at     TEXT,
+        created_by      TEXT REFERENCES users(id),
+        created_at      TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+        updated_at      TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+    );
+
+    -- Pièces jointes (fichiers dans <app_data>/attachments/, nommés par empreinte)
+    CREATE TABLE IF NOT EXISTS attachments (
+        id              TEXT PRIMARY KEY,
+        inspection_id   TEXT NOT NULL REFERENCES inspections(id) ON DELETE CASCADE,
+        criterion_key   TEXT,  -- NULL = pièce jointe à l'inspection
+        file_name       TEXT NOT NULL,
+        mime_type       TEXT NOT NULL,
+        size            INTEGER NOT NULL,
+        sha256          TEXT NOT NULL,
+        caption         TEXT DEFAULT '',
+        created_by      TEXT REFERENCES users(id),
+        created_at      TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+    );
+
+    -- Définitions des grilles utilisées par des inspections (figées)
+    CREATE TABLE IF NOT EXISTS grid_versions (
+        grid_id     TEXT NOT NULL,
+        version     TEXT NOT NULL,
+        definition  TEXT NOT NULL,  -- GridInfo JSON
+        created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+        PRIMARY KEY(grid_id, version)
+    );
+
+    -- Audit trail
+    CREATE TABLE IF NOT EXISTS audit_log (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp   TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+        user_id     TEXT REFERENCES users(id),
+        username    TEXT,
+        action      TEXT NOT NULL,
+        entity_type TEXT,  -- 'inspection','response','user','session'
+        entity_id   TEXT,
+        details     TEXT,  -- JSON libre
+        ip_info     TEXT
+    );
+
+    -- Index pour performance
+    CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit_log(timestamp);
+    CREATE INDEX IF NOT EXISTS idx_audit_user ON audit_log(user_id);
+    CREATE INDEX IF NOT EXISTS idx_audit_entity ON audit_log(entity_type, entity_id);
+    CREATE INDEX IF NOT EXISTS idx_responses_insp ON responses(inspection_id);
+    CREATE INDEX IF NOT EXISTS idx_actions_insp ON corrective_actions(inspection_id);
+    CREATE INDEX IF NOT EXISTS idx_actions_due ON corrective_actions(status, due_date);
+    CREATE INDEX IF NOT EXISTS idx_attachments_insp ON attachments(inspection_id);
+    CREATE INDEX IF NOT EXISTS idx_attachments_sha ON attachments(sha256);
+    CREATE INDEX IF NOT EXISTS idx_inspections_status ON inspections(status);
+    CREATE INDEX IF NOT EXISTS idx_inspections_user ON inspections(created_by);
+";
+
+// Clés des critères des grilles intégrées d'origine, dans l'ordre de leurs
+// identifiants numériques (id = rang + 1). Figées : une migration publiée ne
+// doit dépendre ni du registre courant ni des fichiers de grilles.
+const LEGACY_OFFICINE_KEYS: &[&str] = &[
+    "1.01", "1.02", "1.03", "1.04", "1.05", "1.06", "1.07", "1.08", "1.09", "2.01", "2.02", "2.03",
+    "2.04", "2.05", "2.06", "3.01", "3.02", "3.03", "3.04", "3.05", "3.06", "3.07", "3.08", "3.09",
+    "3.10", "3.11", "3.12", "4.01", "4.02", "4.03", "4.04", "4.05", "4.06", "4.07", "4.08", "4.09",
+    "4.10", "4.11", "4.12", "4.13", "4.14", "4.15", "4.16", "5.01", "5.02", "5.03", "5.04", "5.05",
+    "5.06", "5.07", "5.08", "5.09", "5.10", "5.11", "5.12", "5.13", "5.14", "5.15", "5.16", "5.17",
+    "6.01", "6.02", "6.03", "6.04", "6.05", "6.06", "6.07", "7.01", "8.01", "8.02", "8.03", "8.04",
+    "8.05", "8.06", "8.07", "8.08", "8.09", "8.10", "8.11", "8.12", "8.13", "8.14", "8.15", "8.16",
+    "8.17", "8.18", "8.19", "9.01", "9.02", "9.03", "9.04", "10.01", "11.01", "11.02", "11.03",
+    "11.04", "11.05", "12.01", "12.02", "12.03", "13.01", "13.02", "13.03", "13.04",
+];
+
+const LEGACY_GROSSISTE_KEYS: &[&str] = &[
+    "1.01", "1.02", "1.03", "1.04", "1.05", "1.06", "1.07", "1.08", "2.01", "2.02", "2.03", "2.04",
+    "2.05", "2.06", "3.01", "3.02", "3.03", "3.04", "3.05", "3.06", "3.07", "3.08", "3.09", "3.10",
+    "3.11", "3.12", "3.13", "4.01", "4.02", "4.03", "4.04", "4.05", "4.06", "4.07", "5.01", "5.02",
+    "5.03", "5.04", "6.01", "6.02", "6.03", "6.04", "6.05", "7.01", "7.02", "7.03", "8.01", "8.02",
+    "8.03", "8.04", "9.01", "9.02", "9.03", "9.04", "9.05", "9.06", "10.01", "10.02", "10.03",
+    "11.01", "11.02", "11.03", "11.04", "11.05", "11.06", "11.07", "12.01", "12.02", "12.03",
+    "13.01", "13.02", "13.03", "13.04", "14.01", "14.02", "14.03", "14.04", "14.05", "14.06",
+    "14.07", "15.01", "15.02", "15.03", "15.04", "16.01", "16.02", "16.03", "16.04", "16.05",
+    "16.06", "16.07", "17.01", "17.02", "17.03", "17.04", "17.05", "18.01", "18.02", "18.03",
+    "18.04",
+];
+
+fn legacy_key(grid_id: &str, criterion_id: u32) -> Option<&'static str> {
+    let keys = match grid_id {
+        "officine" => LEGACY_OFFICINE_KEYS,
+        "grossiste" => LEGACY_GROSSISTE_KEYS,
+        _ => return None,
+    };
+    keys.get((criterion_id as usize).checked_sub(1)?).copied()
+}
+
+/// Anciennes bases : `responses.criterion_id` (compteur du CriterionBuilder)
+/// est converti en `criterion_key` d'après les grilles intégrées d'origine.
+/// Un id inconnu est conservé sous la forme "#<id>".
+fn migrate_response_keys(conn: &Connection) -> rusqlite::Result<()> {
+    if !column_exists(conn, "responses", "criterion_id")? {
+        return Ok(());
+    }
+
+    let rows: Vec<(i64, u32, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT r.id, r.criterion_id, i.grid_id FROM responses r JOIN inspections i ON r.inspection_id = i.id"
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows
+    };
+
+    conn.execute_batch("
+        ALTER TABLE responses RENAME TO responses_old;
+        CREATE TABLE responses (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            inspection_id   TEXT NOT NULL REFERENCES inspections(id) ON DELETE CASCADE,
+            criterion_key   TEXT NOT NULL,
+            conforme        INTEGER,
+            observation     TEXT DEFAULT '',
+            updated_by      TEXT REFERENCES users(id),
+            updated_at      TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            UNIQUE(inspection_id, criterion_key)
+        );
+    ")?;
+    for (id, criterion_id, grid_id) in rows {
+        let key = legacy_key(&grid_id, criterion_id)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("#{}", criterion_id));
+        conn.execute(
+            "INSERT INTO responses (id, inspection_id, criterion_key, conforme, observation, updated_by, updated_at)
+             SELECT id, inspection_id, ?1, conforme, observation, updated_by, updated_at FROM responses_old WHERE id = ?2",
+            params![key, id],
+        )?;
+    }
+    conn.execute_batch("
+        DROP TABLE responses_old;
+        CREATE INDEX IF NOT EXISTS idx_responses_insp ON responses(inspection_id);
+    ")
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        &format!("SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name = ?1", table),
+        params![column], |r| r.get(0)
+    )
+}
+
+/// Version des grilles intégrées avec laquelle les anciennes inspections
+/// ont été remplies
+const LEGACY_GRID_VERSION: &str = "1";
+
+/// Anciennes bases : les inspections existantes ont été remplies avec les
+/// grilles intégrées version 1, on les y rattache et on fige cette version.
+/// La version 1 ne diffère de la version 2 que par la gravité des critères :
+/// sans gravité (tous majeurs, poids par défaut), comme lors du remplissage.
+fn migrate_grid_versions(conn: &Connection) -> rusqlite::Result<()> {
+    if column_exists(conn, "inspections", "grid_version")? {
+        return Ok(());
+    }
+    conn.execute("ALTER TABLE inspections ADD COLUMN grid_version TEXT", [])?;
+    for mut grid in crate::grids::builtin() {
+        grid.version = LEGACY_GRID_VERSION.to_string();
+        for criterion in grid.sections.iter_mut().flat_map(|s| s.items.iter_mut()) {
+            criterion.severity = crate::grid::Severity::default();
+            criterion.weight = None;
+        }
+        let pinned = conn.execute(
+            "UPDATE inspections SET grid_version = ?1 WHERE grid_id = ?2 AND grid_version IS NULL",
+            params![grid.version, grid.id],
+        )?;
+        if pinned > 0 {
+            snapshot_grid(conn, &grid)?;
+        }
+    }
+    Ok(())
+}
+
+/// Fige la définition d'une grille (sans écraser une version déjà enregistrée)
+pub fn snapshot_grid(conn: &Connection, grid: &crate::grid::GridInfo) -> rusqlite::Result<()> {
+    let definition = serde_json::to_string(grid).unwrap_or_default();
+    conn.execute(
+        "INSERT OR IGNORE INTO grid_versions (grid_id, version, definition) VALUES (?1, ?2, ?3)",
+        params![grid.id, grid.version, definition],
+    )?;
+    Ok(())
+}
+
+/// Nom normalisé tel que calculé lors de la création du registre
+/// (copie figée de `establishments::normalize_name`)
+fn legacy_name_key(name: &str) -> String {
+    let folded: String = name.to_lowercase().chars().map(|c| match c {
+        'à' | 'â' | 'ä' | 'á' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'î' | 'ï' | 'í' => 'i',
+        'ô' | 'ö' | 'ó' => 'o',
+        'ù' | 'û' | 'ü' | 'ú' => 'u',
+        'ç' => 'c',
+        c if c.is_alphanumeric() => c,
+        _ => ' ',
+    }).collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Anciennes bases : les noms libres de `inspections.establishment` sont
+/// regroupés par nom normalisé, chaque groupe devient un établissement.
+fn migrate_establishments(conn: &Connection) -> rusqlite::Result<()> {
+    if !column_exists(conn, "inspections", "establishment_id")? {
+        conn.execute("ALTER TABLE inspections ADD COLUMN establishment_id TEXT REFERENCES establishments(id)", [])?;
+
+        let rows: Vec<(String, String, String)> = {
+            let mut stmt = conn.prepare(
+                "SELECT id, establishment, grid_id FROM inspections
+                 WHERE TRIM(COALESCE(establishment, '')) != '' ORDER BY created_at"
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            rows
+        };
+        for (inspection_id, name, grid_id) in rows {
+            let name_key = legacy_name_key(&name);
+            let existing: Option<String> = conn.query_row(
+                "SELECT id FROM establishments WHERE name_key = ?1 ORDER BY created_at LIMIT 1",
+                params![name_key], |r| r.get(0)
+            ).optional()?;
+            let establishment_id = match existing {
+                Some(id) => id,
+                None => {
+                    let id = uuid::Uuid::new_v4().to_string();
+                    conn.execute(
+                        "INSERT INTO establishments (id, name, name_key, establishment_type) VALUES (?1,?2,?3,?4)",
+                        params![id, name.trim(), name_key, grid_id],
+                    )?;
+                    id
+                }
+            };
             conn.execute(
-                "INSERT INTO users (id, username, full_name, role, password_hash) VALUES (?1,?2,?3,?4,?5)",
-                params![id, "admin", "Administrateur", "admin", hash],
-            ).ok();
+                "UPDATE inspections SET establishment_id = ?1,
+                 establishment = (SELECT name FROM establishments WHERE id = ?1) WHERE id = ?2",
+                params![establishment_id, inspection_id],
+            )?;
         }
+    }
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_inspections_establishment ON inspections(establishment_id)", [])?;
+    Ok(())
+}
+
+/// Anciennes bases : réponses sans état « non applicable »
+fn migrate_not_applicable(conn: &Connection) -> rusqlite::Result<()> {
+    if !column_exists(conn, "responses", "not_applicable")? {
+        conn.execute("ALTER TABLE responses ADD COLUMN not_applicable INTEGER NOT NULL DEFAULT 0", [])?;
+    }
+    Ok(())
+}
+
+/// Anciennes bases : inspections sans lien vers une inspection d'origine
+fn migrate_follow_ups(conn: &Connection) -> rusqlite::Result<()> {
+    if !column_exists(conn, "inspections", "parent_id")? {
+        conn.execute_batch("
+            ALTER TABLE inspections ADD COLUMN parent_id TEXT REFERENCES inspections(id);
+            ALTER TABLE inspections ADD COLUMN criteria_scope TEXT;
+        ")?;
+    }
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_inspections_parent ON inspections(parent_id)", [])?;
+    Ok(())
+}
+
+/// Réponses antérieures à l'historique : leur état actuel devient la révision 1
+fn migrate_response_revisions(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO response_revisions (inspection_id, criterion_key, revision, conforme, not_applicable, observation, changed_by, changed_at)
+         SELECT inspection_id, criterion_key, 1, conforme, not_applicable, observation, updated_by, updated_at
+         FROM responses r WHERE NOT EXISTS (
+             SELECT 1 FROM response_revisions v WHERE v.inspection_id = r.inspection_id AND v.criterion_key = r.criterion_key)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Changement de mot de passe imposé à la prochaine connexion. Le compte
+/// `admin` / `admin123` créé d'office par les anciennes versions y est soumis.
+fn migrate_must_change_password(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute("ALTER TABLE users ADD COLUMN must_change_password INTEGER NOT NULL DEFAULT 0", [])?;
+    let legacy: Option<(String, String)> = conn.query_row(
+        "SELECT id, password_hash FROM users WHERE username = 'admin'", [], |r| Ok((r.get(0)?, r.get(1)?))
+    ).optional()?;
+    if let Some((id, hash)) = legacy {
+        if bcrypt::verify("admin123", &hash).unwrap_or(false) {
+            conn.execute("UPDATE users SET must_change_password = 1 WHERE id = ?1", params![id])?;
+        }
+    }
+    Ok(())
+}
 
-        Database { conn: Mutex::new(conn) }
+/// Échecs de connexion consécutifs par identifiant (connu ou non)
+fn create_login_attempts(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch("
+        CREATE TABLE login_attempts (
+            username        TEXT PRIMARY KEY,
+            failed_count    INTEGER NOT NULL DEFAULT 0,
+            last_failed_at  TEXT NOT NULL,
+            locked_until    TEXT
+        );
+    ")
+}
+
+/// Date du dernier changement de mot de passe (durée maximale) et
+/// empreintes précédentes (réutilisation interdite)
+fn create_password_history(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch("
+        ALTER TABLE users ADD COLUMN password_changed_at TEXT;
+        UPDATE users SET password_changed_at = updated_at;
+        CREATE TABLE password_history (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id         TEXT NOT NULL REFERENCES users(id),
+            password_hash   TEXT NOT NULL,
+            created_at      TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+        CREATE INDEX idx_password_history_user ON password_history(user_id);
+        INSERT INTO password_history (user_id, password_hash) SELECT id, password_hash FROM users;
+    ")
+}
+
+/// Identifiant propre à chaque session (listage et révocation sans exposer
+/// le jeton) et date de dernière activité pour l'expiration par inactivité.
+/// Les sessions ouvertes sont abandonnées : reconnexion nécessaire.
+fn migrate_sessions(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch("
+        DROP TABLE sessions;
+        CREATE TABLE sessions (
+            id            TEXT PRIMARY KEY,
+            token         TEXT NOT NULL UNIQUE,
+            user_id       TEXT NOT NULL REFERENCES users(id),
+            created_at    TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            last_seen_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            expires_at    TEXT NOT NULL
+        );
+        CREATE INDEX idx_sessions_user ON sessions(user_id);
+    ")
+}
+
+/// Seule l'empreinte du jeton est conservée. Les sessions en cours, dont
+/// le jeton était stocké en clair, sont fermées : reconnexion nécessaire.
+fn hash_session_tokens(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch("
+        DELETE FROM sessions;
+        ALTER TABLE sessions RENAME COLUMN token TO token_hash;
+    ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Schéma des bases créées avant la numérotation des migrations
+    const BASELINE: &str = "
+        CREATE TABLE users (
+            id          TEXT PRIMARY KEY,
+            username    TEXT NOT NULL UNIQUE,
+            full_name   TEXT NOT NULL,
+            role        TEXT NOT NULL DEFAULT 'inspector'
+                        CHECK(role IN ('admin','lead_inspector','inspector','viewer')),
+            password_hash TEXT NOT NULL,
+            active      INTEGER NOT NULL DEFAULT 1,
+            created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            updated_at  TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+        CREATE TABLE sessions (
+            token       TEXT PRIMARY KEY,
+            user_id     TEXT NOT NULL REFERENCES users(id),
+            created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            expires_at  TEXT NOT NULL
+        );
+        CREATE TABLE inspections (
+            id          TEXT PRIMARY KEY,
+            grid_id     TEXT NOT NULL,
+            status      TEXT NOT NULL DEFAULT 'draft'
+                        CHECK(status IN ('draft','in_progress','completed','validated','archived')),
+            date_inspection TEXT,
+            establishment   TEXT,
+            inspection_type TEXT,
+            inspectors      TEXT,
+            created_by  TEXT REFERENCES users(id),
+            validated_by TEXT REFERENCES users(id),
+            validated_at TEXT,
+            created_at  TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            updated_at  TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+        CREATE TABLE responses (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            inspection_id   TEXT NOT NULL REFERENCES inspections(id) ON DELETE CASCADE,
+            criterion_id    INTEGER NOT NULL,
+            conforme        INTEGER,
+            observation     TEXT DEFAULT '',
+            updated_by      TEXT REFERENCES users(id),
+            updated_at      TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            UNIQUE(inspection_id, criterion_id)
+        );
+        CREATE TABLE audit_log (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp   TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+            user_id     TEXT REFERENCES users(id),
+            username    TEXT,
+            action      TEXT NOT NULL,
+            entity_type TEXT,
+            entity_id   TEXT,
+            details     TEXT,
+            ip_info     TEXT
+        );
+        CREATE INDEX idx_audit_timestamp ON audit_log(timestamp);
+        CREATE INDEX idx_audit_user ON audit_log(user_id);
+        CREATE INDEX idx_audit_entity ON audit_log(entity_type, entity_id);
+        CREATE INDEX idx_responses_insp ON responses(inspection_id);
+        CREATE INDEX idx_inspections_status ON inspections(status);
+        CREATE INDEX idx_inspections_user ON inspections(created_by);
+    ";
+
+    fn backup_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("inspection-officine-{}-{}", name, std::process::id()))
+    }
+
+    fn user_version(conn: &Connection) -> u32 {
+        conn.query_row("PRAGMA user_version", [], |r| r.get(0)).unwrap()
+    }
+
+    #[test]
+    fn upgrades_baseline_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(BASELINE).unwrap();
+        let hash = bcrypt::hash("admin123", 4).unwrap();
+        conn.execute("INSERT INTO users (id, username, full_name, role, password_hash) VALUES ('u1', 'admin', 'Administrateur', 'admin', ?1)",
+            params![hash]).unwrap();
+        conn.execute_batch("
+            INSERT INTO sessions (token, user_id, expires_at) VALUES ('jeton-en-clair', 'u1', '2999-01-01 00:00:00');
+            INSERT INTO inspections (id, grid_id, establishment, created_by) VALUES
+                ('i1', 'officine', 'Pharmacie du Port', 'u1'),
+                ('i2', 'grossiste', 'PHARMACIE  du port', 'u1');
+            INSERT INTO responses (inspection_id, criterion_id, conforme, observation, updated_by) VALUES
+                ('i1', 1, 1, '', 'u1'),
+                ('i1', 10, 0, 'SMQ incomplet', 'u1'),
+                ('i1', 999, NULL, 'hors grille', 'u1'),
+                ('i2', 100, 1, '', 'u1');
+        ").unwrap();
+
+        let dir = backup_dir("baseline");
+        migrate(&conn, &dir).unwrap();
+        let backups = std::fs::read_dir(&dir).map(|d| d.count()).unwrap_or(0);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(user_version(&conn), 12);
+        assert_eq!(backups, 1);
+
+        let keys: Vec<(String, Option<bool>)> = conn.prepare("SELECT criterion_key, conforme FROM responses WHERE inspection_id = 'i1' ORDER BY id").unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?))).unwrap()
+            .collect::<rusqlite::Result<_>>().unwrap();
+        assert_eq!(keys, vec![
+            ("1.01".to_string(), Some(true)),
+            ("2.01".to_string(), Some(false)),
+            ("#999".to_string(), None),
+        ]);
+
+        let key: String = conn.query_row("SELECT criterion_key FROM responses WHERE inspection_id = 'i2'", [], |r| r.get(0)).unwrap();
+        assert_eq!(key, "18.04");
+
+        let revisions: Vec<(String, u32, String)> = conn.prepare(
+            "SELECT criterion_key, revision, observation FROM response_revisions WHERE inspection_id = 'i1' ORDER BY criterion_key").unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?))).unwrap()
+            .collect::<rusqlite::Result<_>>().unwrap();
+        assert_eq!(revisions, vec![
+            ("#999".to_string(), 1, "hors grille".to_string()),
+            ("1.01".to_string(), 1, String::new()),
+            ("2.01".to_string(), 1, "SMQ incomplet".to_string()),
+        ]);
+
+        let sessions: u32 = conn.query_row("SELECT COUNT(*) FROM sessions", [], |r| r.get(0)).unwrap();
+        assert_eq!(sessions, 0);
+        assert!(column_exists(&conn, "sessions", "token_hash").unwrap());
+
+        let (version, establishment): (String, Option<String>) = conn.query_row(
+            "SELECT grid_version, establishment_id FROM inspections WHERE id = 'i1'", [], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        assert_eq!(version, "1");
+        assert!(establishment.is_some());
+        let establishments: u32 = conn.query_row("SELECT COUNT(DISTINCT establishment_id) FROM inspections", [], |r| r.get(0)).unwrap();
+        assert_eq!(establishments, 1, "noms identiques une fois normalisés");
+        let definition: String = conn.query_row(
+            "SELECT definition FROM grid_versions WHERE grid_id = 'officine' AND version = '1'", [], |r| r.get(0)).unwrap();
+        let snapshot: crate::grid::GridInfo = serde_json::from_str(&definition).unwrap();
+        assert!(snapshot.sections.iter().flat_map(|s| &s.items)
+            .all(|c| c.severity == crate::grid::Severity::Majeur && c.weight.is_none()));
+
+        let must_change: bool = conn.query_row("SELECT must_change_password FROM users WHERE id = 'u1'", [], |r| r.get(0)).unwrap();
+        assert!(must_change);
+    }
+
+    #[test]
+    fn fresh_install() {
+        let conn = Connection::open_in_memory().unwrap();
+        let dir = backup_dir("fresh");
+        migrate(&conn, &dir).unwrap();
+        assert!(!dir.exists(), "pas de sauvegarde pour une base vide");
+        assert_eq!(user_version(&conn), 12);
+
+        for grid in crate::grids::builtin() {
+            grid.validate().unwrap_or_else(|e| panic!("grille {} invalide : {}", grid.id, e));
+        }
     }
 }
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+// ── Erreurs renvoyées au frontend ──
+
+/// Code stable, utilisé par le frontend pour choisir la conduite à tenir
+/// (retour à l'écran de connexion, message, ...). Ne pas renommer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    InvalidCredentials,
+    AccountDisabled,
+    AccountLocked,
+    TooManyAttempts,
+    SessionExpired,
+    PasswordChangeRequired,
+    Forbidden,
+    NotFound,
+    Validation,
+    Conflict,
+    Locked,
+    Database,
+    Io,
+    Internal,
+}
+
+/// Erreur d'une commande : code stable, message en français destiné à
+/// l'utilisateur, détail technique facultatif (journal, support).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        AppError { code, message: message.into(), details: None }
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    pub fn session_expired() -> Self {
+        Self::new(ErrorCode::SessionExpired, "Session invalide ou expirée")
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self { Self::new(ErrorCode::Forbidden, message) }
+    pub fn not_found(message: impl Into<String>) -> Self { Self::new(ErrorCode::NotFound, message) }
+    pub fn validation(message: impl Into<String>) -> Self { Self::new(ErrorCode::Validation, message) }
+    pub fn conflict(message: impl Into<String>) -> Self { Self::new(ErrorCode::Conflict, message) }
+    pub fn locked(message: impl Into<String>) -> Self { Self::new(ErrorCode::Locked, message) }
+    pub fn internal(message: impl Into<String>) -> Self { Self::new(ErrorCode::Internal, message) }
+
+    /// Pour `map_err` après une lecture par identifiant : aucune ligne
+    /// devient `NotFound` avec ce message, les autres erreurs restent des
+    /// erreurs de base de données.
+    pub fn missing(message: impl Into<String>) -> impl FnOnce(rusqlite::Error) -> AppError {
+        move |e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found(message),
+            e => AppError::from(e),
+        }
+    }
+
+    /// Erreur SQLite avec un message propre à l'opération
+    pub fn database(message: impl Into<String>, e: rusqlite::Error) -> Self {
+        let err = AppError::from(e);
+        match err.code {
+            ErrorCode::Database => AppError { message: message.into(), ..err },
+            _ => err,
+        }
+    }
+
+    /// Erreur de fichier avec un message propre à l'opération
+    pub fn io(message: impl Into<String>, e: std::io::Error) -> Self {
+        Self::new(ErrorCode::Io, message).with_details(e.to_string())
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Élément introuvable"),
+            rusqlite::Error::SqliteFailure(ref f, _) if f.code == rusqlite::ErrorCode::ConstraintViolation =>
+                AppError::conflict("Enregistrement en conflit avec une donnée existante").with_details(e.to_string()),
+            rusqlite::Error::SqliteFailure(ref f, _) if f.code == rusqlite::ErrorCode::DatabaseBusy
+                || f.code == rusqlite::ErrorCode::DatabaseLocked =>
+                AppError::new(ErrorCode::Database, "Base de données occupée, veuillez réessayer").with_details(e.to_string()),
+            _ => AppError::new(ErrorCode::Database, "Erreur de base de données").with_details(e.to_string()),
+        }
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(e: std::sync::PoisonError<T>) -> Self {
+        AppError::internal("Base de données indisponible").with_details(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::io("Erreur d'accès aux fichiers", e)
+    }
+}
+
+impl From<bcrypt::BcryptError> for AppError {
+    fn from(e: bcrypt::BcryptError) -> Self {
+        AppError::internal("Erreur de chiffrement du mot de passe").with_details(e.to_string())
+    }
+}
@@ -0,0 +1,186 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::db::Database;
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Establishment {
+    pub id: String,
+    pub name: String,
+    pub establishment_type: String,   // officine, grossiste, ...
+    pub licence_number: Option<String>,
+    pub owner_pharmacist: Option<String>,
+    pub address: Option<String>,
+    pub commune: Option<String>,
+    pub departement: Option<String>,
+    pub zone_sanitaire: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstablishmentRequest {
+    pub name: String,
+    pub establishment_type: String,
+    pub licence_number: Option<String>,
+    pub owner_pharmacist: Option<String>,
+    pub address: Option<String>,
+    pub commune: Option<String>,
+    pub departement: Option<String>,
+    pub zone_sanitaire: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+const COLUMNS: &str = "id, name, establishment_type, licence_number, owner_pharmacist, address, commune,
+    departement, zone_sanitaire, latitude, longitude, active, created_at, updated_at";
+
+fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Establishment> {
+    Ok(Establishment {
+        id: row.get(0)?, name: row.get(1)?, establishment_type: row.get(2)?,
+        licence_number: row.get(3)?, owner_pharmacist: row.get(4)?, address: row.get(5)?,
+        commune: row.get(6)?, departement: row.get(7)?, zone_sanitaire: row.get(8)?,
+        latitude: row.get(9)?, longitude: row.get(10)?, active: row.get(11)?,
+        created_at: row.get(12)?, updated_at: row.get(13)?,
+    })
+}
+
+/// Forme canonique d'un nom pour le dédoublonnage :
+/// minuscules, sans accents ni ponctuation, espaces réduits.
+pub fn normalize_name(name: &str) -> String {
+    let folded: String = name.to_lowercase().chars().map(|c| match c {
+        'à' | 'â' | 'ä' | 'á' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'î' | 'ï' | 'í' => 'i',
+        'ô' | 'ö' | 'ó' => 'o',
+        'ù' | 'û' | 'ü' | 'ú' => 'u',
+        'ç' => 'c',
+        c if c.is_alphanumeric() => c,
+        _ => ' ',
+    }).collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn validate(req: &EstablishmentRequest) -> Result<(), AppError> {
+    if req.name.trim().is_empty() {
+        return Err(AppError::validation("Le nom de l'établissement est obligatoire"));
+    }
+    if req.establishment_type.trim().is_empty() {
+        return Err(AppError::validation("Le type d'établissement est obligatoire"));
+    }
+    if let Some(lat) = req.latitude {
+        if !(-90.0..=90.0).contains(&lat) { return Err(AppError::validation("Latitude invalide")); }
+    }
+    if let Some(lon) = req.longitude {
+        if !(-180.0..=180.0).contains(&lon) { return Err(AppError::validation("Longitude invalide")); }
+    }
+    Ok(())
+}
+
+// ── CRUD ──
+
+pub fn create_establishment(db: &Database, req: &EstablishmentRequest) -> Result<Establishment, AppError> {
+    validate(req)?;
+    let conn = db.conn.lock()?;
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO establishments (id, name, name_key, establishment_type, licence_number, owner_pharmacist,
+            address, commune, departement, zone_sanitaire, latitude, longitude)
+         VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12)",
+        params![id, req.name.trim(), normalize_name(&req.name), req.establishment_type, req.licence_number,
+            req.owner_pharmacist, req.address, req.commune, req.departement, req.zone_sanitaire,
+            req.latitude, req.longitude],
+    ).map_err(|e| AppError::database("Erreur création établissement", e))?;
+    get_by_id(&conn, &id)
+}
+
+pub fn update_establishment(db: &Database, establishment_id: &str, req: &EstablishmentRequest) -> Result<(), AppError> {
+    validate(req)?;
+    let conn = db.conn.lock()?;
+    let updated = conn.execute(
+        "UPDATE establishments SET name=?1, name_key=?2, establishment_type=?3, licence_number=?4,
+            owner_pharmacist=?5, address=?6, commune=?7, departement=?8, zone_sanitaire=?9,
+            latitude=?10, longitude=?11, updated_at=datetime('now','localtime') WHERE id=?12",
+        params![req.name.trim(), normalize_name(&req.name), req.establishment_type, req.licence_number,
+            req.owner_pharmacist, req.address, req.commune, req.departement, req.zone_sanitaire,
+            req.latitude, req.longitude, establishment_id],
+    )?;
+    if updated == 0 {
+        return Err(AppError::not_found("Établissement non trouvé"));
+    }
+    // Le nom affiché sur les inspections modifiables suit le registre ; les
+    // inspections terminées, validées ou archivées gardent le nom d'origine
+    conn.execute(
+        "UPDATE inspections SET establishment=?1 WHERE establishment_id=?2 AND status IN ('draft','in_progress')",
+        params![req.name.trim(), establishment_id],
+    )?;
+    Ok(())
+}
+
+pub fn get_establishment(db: &Database, establishment_id: &str) -> Result<Establishment, AppError> {
+    let conn = db.conn.lock()?;
+    get_by_id(&conn, establishment_id)
+}
+
+fn get_by_id(conn: &Connection, establishment_id: &str) -> Result<Establishment, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM establishments WHERE id = ?1", COLUMNS),
+        params![establishment_id], from_row,
+    ).map_err(AppError::missing("Établissement non trouvé"))
+}
+
+pub fn list_establishments(db: &Database, search: Option<&str>, establishment_type: Option<&str>) -> Result<Vec<Establishment>, AppError> {
+    let conn = db.conn.lock()?;
+
+    let mut sql = format!("SELECT {} FROM establishments WHERE active = 1", COLUMNS);
+    let mut bind_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    let mut idx = 1;
+
+    if let Some(q) = search {
+        sql.push_str(&format!(" AND (name_key LIKE ?{} OR licence_number LIKE ?{})", idx, idx + 1));
+        bind_values.push(Box::new(format!("%{}%", normalize_name(q))));
+        bind_values.push(Box::new(format!("%{}%", q.trim()))); idx += 2;
+    }
+    if let Some(t) = establishment_type {
+        sql.push_str(&format!(" AND establishment_type = ?{}", idx));
+        bind_values.push(Box::new(t.to_string())); // idx += 1;
+    }
+    sql.push_str(" ORDER BY name");
+
+    let refs: Vec<&dyn rusqlite::types::ToSql> = bind_values.iter().map(|b| b.as_ref()).collect();
+    let mut stmt = conn.prepare(&sql)?;
+    let list = stmt.query_map(refs.as_slice(), from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(list)
+}
+
+/// Désactivation (l'historique des inspections est conservé)
+pub fn delete_establishment(db: &Database, establishment_id: &str) -> Result<(), AppError> {
+    let conn = db.conn.lock()?;
+    conn.execute("UPDATE establishments SET active = 0, updated_at=datetime('now','localtime') WHERE id = ?1",
+        params![establishment_id])?;
+    Ok(())
+}
+
+/// Retrouve un établissement par son nom normalisé, ou le crée.
+/// Utilisé quand une inspection est saisie avec un nom libre.
+pub fn find_or_create(conn: &Connection, name: &str, establishment_type: &str) -> rusqlite::Result<String> {
+    let key = normalize_name(name);
+    let existing: Option<String> = conn.query_row(
+        "SELECT id FROM establishments WHERE name_key = ?1 ORDER BY active DESC, created_at LIMIT 1",
+        params![key], |r| r.get(0)
+    ).ok();
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO establishments (id, name, name_key, establishment_type) VALUES (?1,?2,?3,?4)",
+        params![id, name.trim(), key, establishment_type],
+    )?;
+    Ok(id)
+}
@@ -8,8 +8,11 @@ pub struct GridInfo {
     pub name: String,
     pub code: String,         // ex: "IP-F-0018"
     pub version: String,
+    #[serde(default)]
     pub description: String,
+    #[serde(default)]
     pub icon: String,         // emoji
+    #[serde(default)]
     pub color: String,        // hex accent color
     pub sections: Vec<Section>,
 }
@@ -23,10 +26,170 @@ pub struct Section {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Criterion {
-    pub id: u32,
+    #[serde(default)]
+    pub id: u32,              // renuméroté au chargement des fichiers
+    #[serde(default)]
+    pub key: String,          // clé stable stockée dans responses (ex: "2.03")
+    #[serde(default)]
     pub reference: String,
     pub description: String,
+    #[serde(default)]
     pub pre_opening: bool,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,  // remplace le poids par défaut de la sévérité
+}
+
+/// Gravité d'un écart constaté sur le critère
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critique,
+    #[default]
+    Majeur,
+    Mineur,
+}
+
+impl Severity {
+    pub fn default_weight(self) -> f64 {
+        match self {
+            Severity::Critique => 3.0,
+            Severity::Majeur => 2.0,
+            Severity::Mineur => 1.0,
+        }
+    }
+}
+
+impl Criterion {
+    /// Poids utilisé pour le score pondéré
+    pub fn effective_weight(&self) -> f64 {
+        self.weight.unwrap_or_else(|| self.severity.default_weight())
+    }
+
+    /// Grilles intégrées : `b.pre(...).critique()`
+    pub fn critique(mut self) -> Self {
+        self.severity = Severity::Critique;
+        self
+    }
+
+    /// Grilles intégrées : `b.item(...).mineur()`
+    pub fn mineur(mut self) -> Self {
+        self.severity = Severity::Mineur;
+        self
+    }
+}
+
+impl GridInfo {
+    /// Numérote les critères de 1 à n dans l'ordre de la grille
+    /// (les fichiers de données n'ont pas à fournir les id)
+    pub fn renumber(&mut self) {
+        let mut counter = 0;
+        for section in &mut self.sections {
+            for item in &mut section.items {
+                counter += 1;
+                item.id = counter;
+            }
+        }
+    }
+
+    /// Cherche un critère par sa clé stable
+    pub fn criterion_by_key(&self, key: &str) -> Option<&Criterion> {
+        self.sections.iter().flat_map(|s| s.items.iter()).find(|c| c.key == key)
+    }
+
+    /// Sous-grille limitée aux critères pré-ouverture (►), sans les sections vides
+    pub fn pre_opening_only(&self) -> GridInfo {
+        let mut grid = self.clone();
+        for section in &mut grid.sections {
+            section.items.retain(|c| c.pre_opening);
+        }
+        grid.sections.retain(|s| !s.items.is_empty());
+        grid
+    }
+
+    /// Sous-grille limitée aux critères dont la clé est donnée (contre-visite)
+    pub fn restrict_to(&self, keys: &[String]) -> GridInfo {
+        let mut grid = self.clone();
+        for section in &mut grid.sections {
+            section.items.retain(|c| keys.contains(&c.key));
+        }
+        grid.sections.retain(|s| !s.items.is_empty());
+        grid
+    }
+
+    /// Vérifie qu'une grille est exploitable avant de l'enregistrer
+    pub fn validate(&self) -> Result<(), String> {
+        for (field, value) in [("id", &self.id), ("name", &self.name), ("code", &self.code), ("version", &self.version)] {
+            if value.trim().is_empty() {
+                return Err(format!("Champ « {} » manquant", field));
+            }
+        }
+        if !self.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("Identifiant de grille invalide : {}", self.id));
+        }
+        if self.sections.is_empty() {
+            return Err("La grille ne contient aucune section".to_string());
+        }
+
+        let mut section_ids = std::collections::HashSet::new();
+        let mut criterion_ids = std::collections::HashSet::new();
+        let mut criterion_keys = std::collections::HashSet::new();
+        for section in &self.sections {
+            if !section_ids.insert(section.id) {
+                return Err(format!("Section {} en double", section.id));
+            }
+            if section.title.trim().is_empty() {
+                return Err(format!("Section {} sans titre", section.id));
+            }
+            if section.items.is_empty() {
+                return Err(format!("Section {} sans critère", section.id));
+            }
+            for item in &section.items {
+                if item.description.trim().is_empty() {
+                    return Err(format!("Critère sans description dans la section {}", section.id));
+                }
+                if !criterion_ids.insert(item.id) {
+                    return Err(format!("Critère {} en double", item.id));
+                }
+                if item.key.trim().is_empty() {
+                    return Err(format!("Critère {} sans clé", item.id));
+                }
+                if !criterion_keys.insert(item.key.as_str()) {
+                    return Err(format!("Clé de critère « {} » en double", item.key));
+                }
+                if let Some(w) = item.weight {
+                    if !w.is_finite() || w <= 0.0 {
+                        return Err(format!("Poids invalide pour le critère {}", item.key));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare deux versions de grille ("1" < "2" < "10", "2.1" < "2.10").
+/// Les segments non numériques sont comparés comme du texte.
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ord = match (x.trim().parse::<u64>(), y.trim().parse::<u64>()) {
+                    (Ok(nx), Ok(ny)) => nx.cmp(&ny),
+                    _ => x.cmp(y),
+                };
+                if ord.is_ne() {
+                    return ord;
+                }
+            }
+        }
+    }
 }
 
 // ── Helper pour construire les critères ──
@@ -40,23 +203,28 @@ impl CriterionBuilder {
         Self { counter: 0 }
     }
 
-    pub fn next(&mut self, reference: &str, description: &str, pre_opening: bool) -> Criterion {
+    /// `key` doit rester identique d'une révision de la grille à l'autre :
+    /// un critère inséré reçoit une nouvelle clé, il ne renumérote pas les suivants.
+    pub fn next(&mut self, key: &str, reference: &str, description: &str, pre_opening: bool) -> Criterion {
         self.counter += 1;
         Criterion {
             id: self.counter,
+            key: key.to_string(),
             reference: reference.to_string(),
             description: description.to_string(),
             pre_opening,
+            severity: Severity::default(),
+            weight: None,
         }
     }
 
     /// Shortcut: not pre-opening
-    pub fn item(&mut self, reference: &str, description: &str) -> Criterion {
-        self.next(reference, description, false)
+    pub fn item(&mut self, key: &str, reference: &str, description: &str) -> Criterion {
+        self.next(key, reference, description, false)
     }
 
     /// Shortcut: pre-opening item (►)
-    pub fn pre(&mut self, reference: &str, description: &str) -> Criterion {
-        self.next(reference, description, true)
+    pub fn pre(&mut self, key: &str, reference: &str, description: &str) -> Criterion {
+        self.next(key, reference, description, true)
     }
 }
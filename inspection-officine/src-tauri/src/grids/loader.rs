@@ -0,0 +1,52 @@
+// ══════════════════════════════════════════════════════
+// GRILLES EN FICHIERS DE DONNÉES
+//
+// Chaque fichier  <app_data>/grids/*.json  ou  *.toml  décrit une
+// grille complète (même structure que GridInfo). Les id de critères
+// sont facultatifs : ils sont renumérotés dans l'ordre du fichier.
+// La clé (key) est obligatoire et unique dans la grille : c'est elle qui
+// rattache les réponses d'une révision à l'autre.
+// ══════════════════════════════════════════════════════
+
+use std::path::Path;
+use crate::grid::GridInfo;
+
+/// Charge et valide un fichier de grille
+pub fn load_file(path: &Path) -> Result<GridInfo, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Lecture impossible : {}", e))?;
+
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let mut grid: GridInfo = match ext.as_str() {
+        "json" => serde_json::from_str(&content).map_err(|e| format!("JSON invalide : {}", e))?,
+        "toml" => toml::from_str(&content).map_err(|e| format!("TOML invalide : {}", e))?,
+        _ => return Err(format!("Extension non supportée : {}", ext)),
+    };
+
+    grid.renumber();
+    grid.validate()?;
+    Ok(grid)
+}
+
+/// Charge toutes les grilles d'un dossier (ordre alphabétique des fichiers).
+/// Les fichiers invalides sont écartés et leur erreur renvoyée à part.
+pub fn load_dir(dir: &Path) -> (Vec<GridInfo>, Vec<String>) {
+    let mut grids = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut paths: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path())
+            .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("json" | "toml")))
+            .collect(),
+        Err(_) => return (grids, errors),
+    };
+    paths.sort();
+
+    for path in paths {
+        match load_file(&path) {
+            Ok(grid) => grids.push(grid),
+            Err(e) => errors.push(format!("{} : {}", path.display(), e)),
+        }
+    }
+    (grids, errors)
+}
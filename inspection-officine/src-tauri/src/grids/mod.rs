@@ -1,23 +1,70 @@
 // ══════════════════════════════════════════════════════
 // REGISTRE DES GRILLES
 //
-// Pour ajouter une nouvelle grille :
+// Grilles intégrées (par défaut) :
 //   1. Créer un fichier  grids/mon_nom.rs
 //   2. Implémenter  pub fn build() -> GridInfo { ... }
 //   3. Ajouter ici :  pub mod mon_nom;
-//   4. L'ajouter dans  all() ci-dessous
+//   4. L'ajouter dans  builtin() ci-dessous
+//
+// Grilles en fichiers de données (sans recompiler) :
+//   déposer un  .json  ou  .toml  dans  <app_data>/grids/
+//   (voir loader.rs).
+//
+// Versions : une grille est identifiée par id + version. La version la
+// plus récente de chaque id est la version courante ; les anciennes
+// restent disponibles pour les inspections réalisées avec elles.
+//
+// Les fichiers sont lus une seule fois, au démarrage : une modification
+// est prise en compte au lancement suivant.
 // ══════════════════════════════════════════════════════
 
 pub mod officine;
 pub mod grossiste;
+pub mod loader;
 // pub mod pui;           // <- décommenter quand prêt
 // pub mod bpf;           // <- décommenter quand prêt
 // pub mod up_plantes;    // <- décommenter quand prêt
 
-use crate::grid::GridInfo;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use crate::grid::{compare_versions, GridInfo};
 
-/// Retourne toutes les grilles disponibles
-pub fn all() -> Vec<GridInfo> {
+/// Grilles chargées au démarrage (intégrées et fichiers) et erreurs de chargement
+struct Registry {
+    versions: Vec<GridInfo>,
+    errors: Vec<String>,
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// Charge les grilles en fichiers du dossier (appelé une fois au démarrage)
+pub fn init(dir: PathBuf) {
+    std::fs::create_dir_all(&dir).ok();
+    let (files, errors) = loader::load_dir(&dir);
+    REGISTRY.set(Registry { versions: merge(files), errors }).ok();
+}
+
+fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(|| Registry { versions: merge(Vec::new()), errors: Vec::new() })
+}
+
+/// Grilles intégrées complétées par les fichiers. Un fichier de même
+/// id + version qu'une grille intégrée la remplace (correction sans
+/// changement de version).
+fn merge(files: Vec<GridInfo>) -> Vec<GridInfo> {
+    let mut grids = builtin();
+    for grid in files {
+        match grids.iter_mut().find(|g| g.id == grid.id && g.version == grid.version) {
+            Some(existing) => *existing = grid,
+            None => grids.push(grid),
+        }
+    }
+    grids
+}
+
+/// Grilles compilées dans l'application
+pub fn builtin() -> Vec<GridInfo> {
     vec![
         officine::build(),
         grossiste::build(),
@@ -27,7 +74,38 @@ pub fn all() -> Vec<GridInfo> {
     ]
 }
 
-/// Cherche une grille par son id
+/// Toutes les versions connues
+pub fn all_versions() -> &'static [GridInfo] {
+    &registry().versions
+}
+
+/// Retourne toutes les grilles disponibles (version courante de chacune)
+pub fn all() -> Vec<GridInfo> {
+    let mut latest: Vec<&GridInfo> = Vec::new();
+    for grid in all_versions() {
+        match latest.iter_mut().find(|g| g.id == grid.id) {
+            Some(existing) => {
+                if compare_versions(&grid.version, &existing.version).is_gt() {
+                    *existing = grid;
+                }
+            }
+            None => latest.push(grid),
+        }
+    }
+    latest.into_iter().cloned().collect()
+}
+
+/// Erreurs de chargement des grilles en fichiers
+pub fn load_errors() -> Vec<String> {
+    registry().errors.clone()
+}
+
+/// Cherche une grille par son id (version courante)
 pub fn find(id: &str) -> Option<GridInfo> {
     all().into_iter().find(|g| g.id == id)
 }
+
+/// Cherche une version précise d'une grille
+pub fn find_version(id: &str, version: &str) -> Option<GridInfo> {
+    all_versions().iter().find(|g| g.id == id && g.version == version).cloned()
+}
@@ -2,23 +2,35 @@
 
 mod grid;
 mod grids;
+mod config;
 mod db;
+mod error;
 mod users;
 mod audit;
 mod storage;
+mod establishments;
+mod capa;
+mod attachments;
+mod revisions;
 
 use grid::{GridInfo, Section};
+use config::PasswordPolicy;
 use db::Database;
-use users::{CreateUserRequest, UpdateUserRequest, SessionInfo, User};
+use error::AppError;
+use users::{CreateUserRequest, InitialAdminRequest, UpdateUserRequest, SessionInfo, SessionSummary, User};
 use audit::{AuditEntry, AuditFilter};
-use storage::{SavedInspection, SavedResponse, CreateInspectionRequest};
+use establishments::{Establishment, EstablishmentRequest};
+use attachments::{AddAttachmentRequest, Attachment};
+use revisions::{ResponseRevision, RevisionDiff};
+use capa::{ActionStatus, CorrectiveAction, CreateActionRequest, UpdateActionRequest};
+use storage::{SavedInspection, SavedResponse, CreateInspectionRequest, InspectionScore, InspectionStatus, InspectionComparison, FollowUpReport, ResponseInput, BatchSaveSummary, IntegrityReport};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
 // ── Grid summary (pour la sélection) ──
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GridSummary {
-    pub id: String, pub name: String, pub code: String, pub description: String,
+    pub id: String, pub name: String, pub code: String, pub version: String, pub description: String,
     pub icon: String, pub color: String, pub criteria_count: usize, pub section_count: usize,
 }
 
@@ -27,7 +39,7 @@ pub struct GridSummary {
 #[tauri::command]
 fn list_grids() -> Vec<GridSummary> {
     grids::all().iter().map(|g| GridSummary {
-        id: g.id.clone(), name: g.name.clone(), code: g.code.clone(),
+        id: g.id.clone(), name: g.name.clone(), code: g.code.clone(), version: g.version.clone(),
         description: g.description.clone(), icon: g.icon.clone(), color: g.color.clone(),
         criteria_count: g.sections.iter().map(|s| s.items.len()).sum(),
         section_count: g.sections.len(),
@@ -35,53 +47,111 @@ fn list_grids() -> Vec<GridSummary> {
 }
 
 #[tauri::command]
-fn get_grid(grid_id: String) -> Option<GridInfo> { grids::find(&grid_id) }
+fn get_grid(grid_id: String, version: Option<String>) -> Option<GridInfo> {
+    match version {
+        Some(v) => grids::find_version(&grid_id, &v),
+        None => grids::find(&grid_id),
+    }
+}
+
+#[tauri::command]
+fn get_sections(grid_id: String, inspection_type: Option<String>) -> Vec<Section> {
+    let pre_opening = inspection_type.as_deref().is_some_and(storage::is_pre_opening);
+    grids::find(&grid_id)
+        .map(|g| if pre_opening { g.pre_opening_only() } else { g })
+        .map(|g| g.sections)
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+fn cmd_grid_load_errors(database: State<Database>, token: String) -> Result<Vec<String>, AppError> {
+    require_role(&database, &token, &["admin", "lead_inspector"])?;
+    Ok(grids::load_errors())
+}
+
+// ════════════════════ PREMIER LANCEMENT ════════════════════
 
 #[tauri::command]
-fn get_sections(grid_id: String) -> Vec<Section> {
-    grids::find(&grid_id).map(|g| g.sections).unwrap_or_default()
+fn cmd_needs_setup(database: State<Database>) -> Result<bool, AppError> {
+    users::needs_setup(&database)
+}
+
+/// Règles de mot de passe en vigueur (affichées avant la saisie)
+#[tauri::command]
+fn cmd_get_password_policy() -> PasswordPolicy {
+    config::get().password.clone()
+}
+
+/// Création de l'administrateur initial (uniquement tant qu'aucun n'existe)
+#[tauri::command]
+fn cmd_setup_admin(database: State<Database>, req: InitialAdminRequest) -> Result<User, AppError> {
+    let user = users::create_initial_admin(&database, &req)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "SETUP_ADMIN", "user", &user.id, "");
+    Ok(user)
 }
 
 // ════════════════════ AUTH ════════════════════
 
 #[tauri::command]
-fn cmd_login(database: State<Database>, username: String, password: String) -> Result<SessionInfo, String> {
-    let result = users::login(&database, &username, &password)?;
+fn cmd_login(database: State<Database>, username: String, password: String) -> Result<SessionInfo, AppError> {
+    let result = users::login(&database, &username, &password).map_err(|f| {
+        let details = serde_json::json!({ "code": f.error.code, "attempts": f.failed_count }).to_string();
+        audit::log_action(&database, f.user_id.as_deref(), Some(&username),
+            "LOGIN_FAILED", Some("user"), f.user_id.as_deref(), Some(&details));
+        if f.locked_now {
+            audit::log_action(&database, f.user_id.as_deref(), Some(&username),
+                "ACCOUNT_LOCKED", Some("user"), f.user_id.as_deref(), Some(&details));
+        }
+        AppError::from(f)
+    })?;
     audit::log_action(&database, Some(&result.user.id), Some(&result.user.username),
-        "LOGIN", Some("session"), Some(&result.token), None);
+        "LOGIN", Some("session"), Some(&result.session_id), None);
     Ok(result)
 }
 
 #[tauri::command]
-fn cmd_logout(database: State<Database>, token: String) -> Result<(), String> {
-    if let Ok(user) = users::validate_session(&database, &token) {
+fn cmd_logout(database: State<Database>, token: String) -> Result<(), AppError> {
+    let user = users::session_user(&database, &token).ok();
+    if let (Some(user), Some(session_id)) = (user, users::logout(&database, &token)?) {
         audit::log_user_action(&database, &user.id, &user.username,
-            "LOGOUT", "session", &token, "");
+            "LOGOUT", "session", &session_id, "");
     }
-    users::logout(&database, &token)
+    Ok(())
 }
 
 #[tauri::command]
-fn cmd_validate_session(database: State<Database>, token: String) -> Result<User, String> {
-    users::validate_session(&database, &token)
+fn cmd_validate_session(database: State<Database>, token: String) -> Result<User, AppError> {
+    users::session_user(&database, &token)
+}
+
+/// Changement de son propre mot de passe (seule commande acceptée tant
+/// qu'un changement est imposé)
+#[tauri::command]
+fn cmd_change_own_password(database: State<Database>, token: String, current_password: String, new_password: String) -> Result<(), AppError> {
+    let user = users::session_user(&database, &token)?;
+    users::change_own_password(&database, &user.id, &token, &current_password, &new_password)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "CHANGE_OWN_PASSWORD", "user", &user.id, "");
+    Ok(())
 }
 
 // ════════════════════ UTILISATEURS ════════════════════
 
-fn require_role(db: &Database, token: &str, roles: &[&str]) -> Result<User, String> {
+fn require_role(db: &Database, token: &str, roles: &[&str]) -> Result<User, AppError> {
     let user = users::validate_session(db, token)?;
     if roles.contains(&user.role.as_str()) { Ok(user) }
-    else { Err(format!("Accès refusé. Rôle requis : {}", roles.join(" ou "))) }
+    else { Err(AppError::forbidden(format!("Accès refusé. Rôle requis : {}", roles.join(" ou ")))) }
 }
 
 #[tauri::command]
-fn cmd_list_users(database: State<Database>, token: String) -> Result<Vec<User>, String> {
+fn cmd_list_users(database: State<Database>, token: String) -> Result<Vec<User>, AppError> {
     require_role(&database, &token, &["admin", "lead_inspector"])?;
     users::list_users(&database)
 }
 
 #[tauri::command]
-fn cmd_create_user(database: State<Database>, token: String, req: CreateUserRequest) -> Result<User, String> {
+fn cmd_create_user(database: State<Database>, token: String, req: CreateUserRequest) -> Result<User, AppError> {
     let admin = require_role(&database, &token, &["admin"])?;
     let user = users::create_user(&database, &req)?;
     audit::log_user_action(&database, &admin.id, &admin.username,
@@ -91,7 +161,7 @@ fn cmd_create_user(database: State<Database>, token: String, req: CreateUserRequ
 }
 
 #[tauri::command]
-fn cmd_update_user(database: State<Database>, token: String, user_id: String, req: UpdateUserRequest) -> Result<(), String> {
+fn cmd_update_user(database: State<Database>, token: String, user_id: String, req: UpdateUserRequest) -> Result<(), AppError> {
     let admin = require_role(&database, &token, &["admin"])?;
     users::update_user(&database, &user_id, &req)?;
     audit::log_user_action(&database, &admin.id, &admin.username,
@@ -101,16 +171,49 @@ fn cmd_update_user(database: State<Database>, token: String, user_id: String, re
 }
 
 #[tauri::command]
-fn cmd_change_password(database: State<Database>, token: String, user_id: String, new_password: String) -> Result<(), String> {
+fn cmd_change_password(database: State<Database>, token: String, user_id: String, new_password: String) -> Result<(), AppError> {
     let admin = require_role(&database, &token, &["admin"])?;
-    users::change_password(&database, &user_id, &new_password)?;
+    users::change_password(&database, &user_id, &new_password, user_id != admin.id, &token)?;
     audit::log_user_action(&database, &admin.id, &admin.username,
         "CHANGE_PASSWORD", "user", &user_id, "");
     Ok(())
 }
 
 #[tauri::command]
-fn cmd_delete_user(database: State<Database>, token: String, user_id: String) -> Result<(), String> {
+fn cmd_unlock_user(database: State<Database>, token: String, user_id: String) -> Result<(), AppError> {
+    let admin = require_role(&database, &token, &["admin"])?;
+    users::unlock_user(&database, &user_id)?;
+    audit::log_user_action(&database, &admin.id, &admin.username,
+        "UNLOCK_USER", "user", &user_id, "");
+    Ok(())
+}
+
+#[tauri::command]
+fn cmd_list_sessions(database: State<Database>, token: String, user_id: String) -> Result<Vec<SessionSummary>, AppError> {
+    require_role(&database, &token, &["admin"])?;
+    users::list_sessions(&database, &user_id, &token)
+}
+
+#[tauri::command]
+fn cmd_revoke_session(database: State<Database>, token: String, session_id: String) -> Result<(), AppError> {
+    let admin = require_role(&database, &token, &["admin"])?;
+    let user_id = users::revoke_session(&database, &session_id)?;
+    audit::log_user_action(&database, &admin.id, &admin.username,
+        "REVOKE_SESSION", "user", &user_id, &session_id);
+    Ok(())
+}
+
+#[tauri::command]
+fn cmd_revoke_user_sessions(database: State<Database>, token: String, user_id: String) -> Result<usize, AppError> {
+    let admin = require_role(&database, &token, &["admin"])?;
+    let count = users::revoke_user_sessions(&database, &user_id)?;
+    audit::log_user_action(&database, &admin.id, &admin.username,
+        "REVOKE_SESSIONS", "user", &user_id, &format!("{} session(s)", count));
+    Ok(count)
+}
+
+#[tauri::command]
+fn cmd_delete_user(database: State<Database>, token: String, user_id: String) -> Result<(), AppError> {
     let admin = require_role(&database, &token, &["admin"])?;
     users::delete_user(&database, &user_id)?;
     audit::log_user_action(&database, &admin.id, &admin.username,
@@ -121,47 +224,96 @@ fn cmd_delete_user(database: State<Database>, token: String, user_id: String) ->
 // ════════════════════ INSPECTIONS PERSISTANTES ════════════════════
 
 #[tauri::command]
-fn cmd_create_inspection(database: State<Database>, token: String, req: CreateInspectionRequest) -> Result<String, String> {
+fn cmd_create_inspection(database: State<Database>, token: String, req: CreateInspectionRequest) -> Result<String, AppError> {
     let user = users::validate_session(&database, &token)?;
     let id = storage::create_inspection(&database, &req, &user.id)?;
     audit::log_user_action(&database, &user.id, &user.username,
         "CREATE_INSPECTION", "inspection", &id,
-        &format!("{{\"grid\":\"{}\",\"establishment\":\"{}\"}}", req.grid_id, req.establishment));
+        &serde_json::json!({ "grid": req.grid_id, "establishment": req.establishment, "parent": req.parent_id }).to_string());
     Ok(id)
 }
 
 #[tauri::command]
-fn cmd_list_inspections(database: State<Database>, token: String, my_only: bool, status: Option<String>) -> Result<Vec<SavedInspection>, String> {
+fn cmd_list_inspections(database: State<Database>, token: String, my_only: bool, status: Option<String>, establishment_id: Option<String>) -> Result<Vec<SavedInspection>, AppError> {
     let user = users::validate_session(&database, &token)?;
     let user_filter = if my_only || user.role == "inspector" { Some(user.id.as_str()) } else { None };
-    storage::list_inspections(&database, user_filter, status.as_deref())
+    storage::list_inspections(&database, user_filter, status.as_deref(), establishment_id.as_deref())
 }
 
 #[tauri::command]
-fn cmd_get_inspection(database: State<Database>, token: String, inspection_id: String) -> Result<SavedInspection, String> {
+fn cmd_get_inspection(database: State<Database>, token: String, inspection_id: String) -> Result<SavedInspection, AppError> {
     users::validate_session(&database, &token)?;
     storage::get_inspection(&database, &inspection_id)
 }
 
 #[tauri::command]
-fn cmd_get_responses(database: State<Database>, token: String, inspection_id: String) -> Result<Vec<SavedResponse>, String> {
+fn cmd_get_responses(database: State<Database>, token: String, inspection_id: String) -> Result<Vec<SavedResponse>, AppError> {
     users::validate_session(&database, &token)?;
     storage::get_responses(&database, &inspection_id)
 }
 
+#[tauri::command]
+fn cmd_get_inspection_grid(database: State<Database>, token: String, inspection_id: String) -> Result<GridInfo, AppError> {
+    users::validate_session(&database, &token)?;
+    storage::get_inspection_grid(&database, &inspection_id)
+}
+
+#[tauri::command]
+fn cmd_get_score(database: State<Database>, token: String, inspection_id: String) -> Result<InspectionScore, AppError> {
+    users::validate_session(&database, &token)?;
+    storage::get_score(&database, &inspection_id)
+}
+
+#[tauri::command]
+fn cmd_get_follow_up_report(database: State<Database>, token: String, inspection_id: String) -> Result<FollowUpReport, AppError> {
+    users::validate_session(&database, &token)?;
+    storage::get_follow_up_report(&database, &inspection_id)
+}
+
+#[tauri::command]
+fn cmd_compare_with_previous(database: State<Database>, token: String, inspection_id: String) -> Result<InspectionComparison, AppError> {
+    users::validate_session(&database, &token)?;
+    storage::compare_with_previous(&database, &inspection_id)
+}
+
 #[tauri::command]
 fn cmd_save_response(database: State<Database>, token: String, inspection_id: String,
-    criterion_id: u32, conforme: Option<bool>, observation: String) -> Result<(), String> {
+    criterion_key: String, conforme: Option<bool>, not_applicable: Option<bool>, observation: String) -> Result<(), AppError> {
     let user = users::validate_session(&database, &token)?;
-    storage::save_response(&database, &inspection_id, criterion_id, conforme, &observation, &user.id)?;
+    let not_applicable = not_applicable.unwrap_or(false);
+    storage::save_response(&database, &inspection_id, &criterion_key, conforme, not_applicable, &observation, &user.id)?;
     audit::log_user_action(&database, &user.id, &user.username,
-        "SAVE_RESPONSE", "response", &format!("{}:{}", inspection_id, criterion_id),
-        &format!("{{\"conforme\":{},\"has_obs\":{}}}", conforme.map(|b|b.to_string()).unwrap_or("null".into()), !observation.is_empty()));
+        "SAVE_RESPONSE", "response", &format!("{}:{}", inspection_id, criterion_key),
+        &format!("{{\"conforme\":{},\"na\":{},\"has_obs\":{}}}", conforme.map(|b|b.to_string()).unwrap_or("null".into()), not_applicable, !observation.is_empty()));
     Ok(())
 }
 
+/// Enregistrement par lot (synchronisation de notes saisies hors ligne)
 #[tauri::command]
-fn cmd_update_inspection_meta(database: State<Database>, token: String, inspection_id: String, req: CreateInspectionRequest) -> Result<(), String> {
+fn cmd_save_responses(database: State<Database>, token: String, inspection_id: String, responses: Vec<ResponseInput>) -> Result<BatchSaveSummary, AppError> {
+    let user = users::validate_session(&database, &token)?;
+    let summary = storage::save_responses(&database, &inspection_id, &responses, &user.id)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "SAVE_RESPONSES", "inspection", &inspection_id,
+        &serde_json::to_string(&summary).unwrap_or_default());
+    Ok(summary)
+}
+
+#[tauri::command]
+fn cmd_get_response_history(database: State<Database>, token: String, inspection_id: String, criterion_key: String) -> Result<Vec<ResponseRevision>, AppError> {
+    users::validate_session(&database, &token)?;
+    revisions::get_history(&database, &inspection_id, &criterion_key)
+}
+
+#[tauri::command]
+fn cmd_diff_response_revisions(database: State<Database>, token: String, inspection_id: String, criterion_key: String,
+    from_revision: u32, to_revision: u32) -> Result<RevisionDiff, AppError> {
+    users::validate_session(&database, &token)?;
+    revisions::diff_revisions(&database, &inspection_id, &criterion_key, from_revision, to_revision)
+}
+
+#[tauri::command]
+fn cmd_update_inspection_meta(database: State<Database>, token: String, inspection_id: String, req: CreateInspectionRequest) -> Result<(), AppError> {
     let user = users::validate_session(&database, &token)?;
     storage::update_inspection_meta(&database, &inspection_id, &req)?;
     audit::log_user_action(&database, &user.id, &user.username,
@@ -170,20 +322,25 @@ fn cmd_update_inspection_meta(database: State<Database>, token: String, inspecti
 }
 
 #[tauri::command]
-fn cmd_set_inspection_status(database: State<Database>, token: String, inspection_id: String, status: String) -> Result<(), String> {
-    let user = if status == "validated" {
-        require_role(&database, &token, &["admin", "lead_inspector"])?
-    } else {
-        users::validate_session(&database, &token)?
-    };
-    storage::set_status(&database, &inspection_id, &status, Some(&user.id))?;
+fn cmd_set_inspection_status(database: State<Database>, token: String, inspection_id: String, status: String, reason: Option<String>) -> Result<(), AppError> {
+    let user = users::validate_session(&database, &token)?;
+    let to = InspectionStatus::parse(&status)?;
+    let from = storage::get_status(&database, &inspection_id)?;
+    let transition = storage::find_transition(from, to)?;
+    if !transition.roles.contains(&user.role.as_str()) {
+        return Err(AppError::forbidden(format!("Accès refusé. Rôle requis : {}", transition.roles.join(" ou "))));
+    }
+
+    storage::set_status(&database, &inspection_id, from, to, &user.id, reason.as_deref())?;
+    let details = serde_json::json!({ "from": from.as_str(), "to": to.as_str(), "reason": reason }).to_string();
+    let action = if transition.reopen { "REOPEN_INSPECTION".to_string() } else { format!("SET_STATUS_{}", to.as_str().to_uppercase()) };
     audit::log_user_action(&database, &user.id, &user.username,
-        &format!("SET_STATUS_{}", status.to_uppercase()), "inspection", &inspection_id, "");
+        &action, "inspection", &inspection_id, &details);
     Ok(())
 }
 
 #[tauri::command]
-fn cmd_delete_inspection(database: State<Database>, token: String, inspection_id: String) -> Result<(), String> {
+fn cmd_delete_inspection(database: State<Database>, token: String, inspection_id: String) -> Result<(), AppError> {
     let user = require_role(&database, &token, &["admin", "lead_inspector"])?;
     storage::delete_inspection(&database, &inspection_id)?;
     audit::log_user_action(&database, &user.id, &user.username,
@@ -191,16 +348,164 @@ fn cmd_delete_inspection(database: State<Database>, token: String, inspection_id
     Ok(())
 }
 
+/// Contrôle ponctuel de cohérence réponses / grilles
+#[tauri::command]
+fn cmd_check_integrity(database: State<Database>, token: String) -> Result<IntegrityReport, AppError> {
+    let user = require_role(&database, &token, &["admin"])?;
+    let report = storage::check_integrity(&database)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "INTEGRITY_CHECK", "system", "",
+        &serde_json::json!({ "inspections": report.inspections_checked, "rows": report.rows_checked,
+            "issues": report.issues.len() }).to_string());
+    Ok(report)
+}
+
+// ════════════════════ ÉTABLISSEMENTS ════════════════════
+
+#[tauri::command]
+fn cmd_list_establishments(database: State<Database>, token: String, search: Option<String>, establishment_type: Option<String>) -> Result<Vec<Establishment>, AppError> {
+    users::validate_session(&database, &token)?;
+    establishments::list_establishments(&database, search.as_deref(), establishment_type.as_deref())
+}
+
+#[tauri::command]
+fn cmd_get_establishment(database: State<Database>, token: String, establishment_id: String) -> Result<Establishment, AppError> {
+    users::validate_session(&database, &token)?;
+    establishments::get_establishment(&database, &establishment_id)
+}
+
+#[tauri::command]
+fn cmd_create_establishment(database: State<Database>, token: String, req: EstablishmentRequest) -> Result<Establishment, AppError> {
+    let user = require_role(&database, &token, &["admin", "lead_inspector", "inspector"])?;
+    let est = establishments::create_establishment(&database, &req)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "CREATE_ESTABLISHMENT", "establishment", &est.id,
+        &serde_json::to_string(&req).unwrap_or_default());
+    Ok(est)
+}
+
+#[tauri::command]
+fn cmd_update_establishment(database: State<Database>, token: String, establishment_id: String, req: EstablishmentRequest) -> Result<(), AppError> {
+    let user = require_role(&database, &token, &["admin", "lead_inspector", "inspector"])?;
+    establishments::update_establishment(&database, &establishment_id, &req)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "UPDATE_ESTABLISHMENT", "establishment", &establishment_id,
+        &serde_json::to_string(&req).unwrap_or_default());
+    Ok(())
+}
+
+#[tauri::command]
+fn cmd_delete_establishment(database: State<Database>, token: String, establishment_id: String) -> Result<(), AppError> {
+    let user = require_role(&database, &token, &["admin", "lead_inspector"])?;
+    establishments::delete_establishment(&database, &establishment_id)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "DEACTIVATE_ESTABLISHMENT", "establishment", &establishment_id, "");
+    Ok(())
+}
+
+// ════════════════════ ACTIONS CORRECTIVES ════════════════════
+
+#[tauri::command]
+fn cmd_create_corrective_action(database: State<Database>, token: String, req: CreateActionRequest) -> Result<CorrectiveAction, AppError> {
+    let user = require_role(&database, &token, &["admin", "lead_inspector", "inspector"])?;
+    let action = capa::create_action(&database, &req, &user.id)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "CREATE_ACTION", "corrective_action", &action.id,
+        &serde_json::to_string(&req).unwrap_or_default());
+    Ok(action)
+}
+
+#[tauri::command]
+fn cmd_update_corrective_action(database: State<Database>, token: String, action_id: String, req: UpdateActionRequest) -> Result<(), AppError> {
+    let user = require_role(&database, &token, &["admin", "lead_inspector", "inspector"])?;
+    capa::update_action(&database, &action_id, &req)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "UPDATE_ACTION", "corrective_action", &action_id,
+        &serde_json::to_string(&req).unwrap_or_default());
+    Ok(())
+}
+
+#[tauri::command]
+fn cmd_set_corrective_action_status(database: State<Database>, token: String, action_id: String, status: String, evidence: Option<String>) -> Result<(), AppError> {
+    let user = users::validate_session(&database, &token)?;
+    let to = ActionStatus::parse(&status)?;
+    let from = capa::get_action(&database, &action_id)?.status;
+    let roles = from.transition_roles(to)
+        .ok_or_else(|| AppError::forbidden(format!("Transition interdite : {} → {}", from.label(), to.label())))?;
+    if !roles.contains(&user.role.as_str()) {
+        return Err(AppError::forbidden(format!("Accès refusé. Rôle requis : {}", roles.join(" ou "))));
+    }
+    capa::set_action_status(&database, &action_id, from, to, evidence.as_deref(), &user.id)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        &format!("ACTION_{}", to.as_str().to_uppercase()), "corrective_action", &action_id,
+        &serde_json::json!({ "from": from.as_str(), "to": to.as_str(), "evidence": evidence }).to_string());
+    Ok(())
+}
+
+#[tauri::command]
+fn cmd_list_corrective_actions(database: State<Database>, token: String, inspection_id: String) -> Result<Vec<CorrectiveAction>, AppError> {
+    users::validate_session(&database, &token)?;
+    capa::list_actions(&database, &inspection_id)
+}
+
+#[tauri::command]
+fn cmd_list_overdue_actions(database: State<Database>, token: String, establishment_id: Option<String>) -> Result<Vec<CorrectiveAction>, AppError> {
+    users::validate_session(&database, &token)?;
+    capa::list_overdue(&database, establishment_id.as_deref())
+}
+
+// ════════════════════ PIÈCES JOINTES ════════════════════
+
+/// Le fichier est le corps binaire de l'appel ; le jeton (`x-token`) et la
+/// description (`x-attachment`) passent dans les en-têtes
+#[tauri::command]
+fn cmd_add_attachment(database: State<Database>, request: tauri::ipc::Request) -> Result<Attachment, AppError> {
+    let header = |name: &str| request.headers().get(name).and_then(|v| v.to_str().ok()).unwrap_or("");
+    let user = require_role(&database, header("x-token"), &["admin", "lead_inspector", "inspector"])?;
+    let req = AddAttachmentRequest::from_header(header("x-attachment"))?;
+    let tauri::ipc::InvokeBody::Raw(data) = request.body() else {
+        return Err(AppError::validation("Le contenu du fichier doit être envoyé en binaire"));
+    };
+    let attachment = attachments::add_attachment(&database, &req, data, &user.id)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "ADD_ATTACHMENT", "attachment", &attachment.id,
+        &serde_json::json!({ "inspection": attachment.inspection_id, "criterion": attachment.criterion_key,
+            "file": attachment.file_name, "sha256": attachment.sha256 }).to_string());
+    Ok(attachment)
+}
+
+#[tauri::command]
+fn cmd_list_attachments(database: State<Database>, token: String, inspection_id: String, criterion_key: Option<String>) -> Result<Vec<Attachment>, AppError> {
+    users::validate_session(&database, &token)?;
+    attachments::list_attachments(&database, &inspection_id, criterion_key.as_deref())
+}
+
+#[tauri::command]
+fn cmd_get_attachment(database: State<Database>, token: String, attachment_id: String) -> Result<tauri::ipc::Response, AppError> {
+    users::validate_session(&database, &token)?;
+    attachments::get_attachment(&database, &attachment_id).map(tauri::ipc::Response::new)
+}
+
+#[tauri::command]
+fn cmd_delete_attachment(database: State<Database>, token: String, attachment_id: String) -> Result<(), AppError> {
+    let user = require_role(&database, &token, &["admin", "lead_inspector", "inspector"])?;
+    let attachment = attachments::delete_attachment(&database, &attachment_id)?;
+    audit::log_user_action(&database, &user.id, &user.username,
+        "DELETE_ATTACHMENT", "attachment", &attachment_id,
+        &serde_json::json!({ "inspection": attachment.inspection_id, "file": attachment.file_name }).to_string());
+    Ok(())
+}
+
 // ════════════════════ AUDIT ════════════════════
 
 #[tauri::command]
-fn cmd_query_audit(database: State<Database>, token: String, filter: AuditFilter) -> Result<Vec<AuditEntry>, String> {
+fn cmd_query_audit(database: State<Database>, token: String, filter: AuditFilter) -> Result<Vec<AuditEntry>, AppError> {
     require_role(&database, &token, &["admin", "lead_inspector"])?;
     audit::query_audit(&database, &filter)
 }
 
 #[tauri::command]
-fn cmd_count_audit(database: State<Database>, token: String, filter: AuditFilter) -> Result<u32, String> {
+fn cmd_count_audit(database: State<Database>, token: String, filter: AuditFilter) -> Result<u32, AppError> {
     require_role(&database, &token, &["admin", "lead_inspector"])?;
     audit::count_audit(&database, &filter)
 }
@@ -212,26 +517,60 @@ fn main() {
         .unwrap_or_else(|| std::path::PathBuf::from("."))
         .join("abmed-inspections");
 
-    let database = Database::new(app_dir);
+    let config_error = config::init(&app_dir.join("config.toml"));
+    grids::init(app_dir.join("grids"));
+    attachments::init(app_dir.join("attachments"));
+    let database = match Database::new(app_dir.clone()) {
+        Ok(database) => database,
+        Err(e) => {
+            // Pas de fenêtre sans base : message sur la console et dans un fichier
+            let message = match e.details {
+                Some(details) => format!("{}\n{}", e.message, details),
+                None => e.message,
+            };
+            eprintln!("{}", message);
+            std::fs::write(app_dir.join("erreur-demarrage.log"), &message).ok();
+            std::process::exit(1);
+        }
+    };
 
     // Log démarrage
     audit::log_action(&database, None, None, "APP_START", Some("system"), None, None);
+    if let Some(err) = config_error {
+        audit::log_action(&database, None, None, "CONFIG_ERROR", Some("system"), None, Some(&err));
+    }
+    for err in grids::load_errors() {
+        audit::log_action(&database, None, None, "GRID_LOAD_ERROR", Some("grid"), None, Some(&err));
+    }
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .manage(database)
         .invoke_handler(tauri::generate_handler![
             // Grilles
-            list_grids, get_grid, get_sections,
+            list_grids, get_grid, get_sections, cmd_grid_load_errors,
+            // Premier lancement
+            cmd_needs_setup, cmd_setup_admin, cmd_get_password_policy,
             // Auth
-            cmd_login, cmd_logout, cmd_validate_session,
+            cmd_login, cmd_logout, cmd_validate_session, cmd_change_own_password,
             // Utilisateurs
             cmd_list_users, cmd_create_user, cmd_update_user,
-            cmd_change_password, cmd_delete_user,
+            cmd_change_password, cmd_unlock_user, cmd_list_sessions, cmd_revoke_session,
+            cmd_revoke_user_sessions, cmd_delete_user,
             // Inspections
             cmd_create_inspection, cmd_list_inspections, cmd_get_inspection,
-            cmd_get_responses, cmd_save_response, cmd_update_inspection_meta,
-            cmd_set_inspection_status, cmd_delete_inspection,
+            cmd_get_responses, cmd_get_inspection_grid, cmd_get_score,
+            cmd_compare_with_previous, cmd_get_follow_up_report,
+            cmd_save_response, cmd_save_responses, cmd_get_response_history, cmd_diff_response_revisions, cmd_update_inspection_meta,
+            cmd_set_inspection_status, cmd_delete_inspection, cmd_check_integrity,
+            // Établissements
+            cmd_list_establishments, cmd_get_establishment, cmd_create_establishment,
+            cmd_update_establishment, cmd_delete_establishment,
+            // Actions correctives
+            cmd_create_corrective_action, cmd_update_corrective_action,
+            cmd_set_corrective_action_status, cmd_list_corrective_actions, cmd_list_overdue_actions,
+            // Pièces jointes
+            cmd_add_attachment, cmd_list_attachments, cmd_get_attachment, cmd_delete_attachment,
             // Audit
             cmd_query_audit, cmd_count_audit,
         ])
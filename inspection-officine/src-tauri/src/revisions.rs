@@ -0,0 +1,146 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use crate::db::Database;
+use crate::error::AppError;
+
+// ── Historique des réponses ──
+//
+// Chaque état enregistré d'une réponse (verdict, N/A, observation) est
+// conservé comme révision numérotée ; la dernière révision correspond
+// à la ligne de `responses`.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseRevision {
+    pub revision: u32,
+    pub conforme: Option<bool>,
+    pub not_applicable: bool,
+    pub observation: String,
+    pub changed_by: Option<String>,
+    pub changed_by_name: Option<String>,
+    pub changed_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Equal,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextChange {
+    pub kind: ChangeKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub from: ResponseRevision,
+    pub to: ResponseRevision,
+    pub verdict_changed: bool,       // conforme ou N/A modifié
+    pub observation_changed: bool,
+    pub observation: Vec<TextChange>,  // différences mot à mot
+}
+
+const SELECT: &str = "SELECT r.revision, r.conforme, r.not_applicable, r.observation, r.changed_by, u.full_name, r.changed_at
+    FROM response_revisions r LEFT JOIN users u ON r.changed_by = u.id";
+
+fn from_row(row: &rusqlite::Row) -> rusqlite::Result<ResponseRevision> {
+    let conf_raw: Option<i32> = row.get(1)?;
+    Ok(ResponseRevision {
+        revision: row.get(0)?,
+        conforme: conf_raw.map(|v| v != 0),
+        not_applicable: row.get::<_,bool>(2).unwrap_or(false),
+        observation: row.get::<_,String>(3).unwrap_or_default(),
+        changed_by: row.get(4)?, changed_by_name: row.get(5)?, changed_at: row.get(6)?,
+    })
+}
+
+/// Ajoute une révision si l'état diffère de la dernière enregistrée.
+/// Retourne le numéro de la nouvelle révision (None si rien n'a changé).
+pub fn record(conn: &Connection, inspection_id: &str, criterion_key: &str, conforme: Option<i32>,
+    not_applicable: bool, observation: &str, user_id: &str) -> rusqlite::Result<Option<u32>> {
+    let last: Option<(u32, Option<i32>, bool, String)> = conn.query_row(
+        "SELECT revision, conforme, not_applicable, COALESCE(observation, '') FROM response_revisions
+         WHERE inspection_id = ?1 AND criterion_key = ?2 ORDER BY revision DESC LIMIT 1",
+        params![inspection_id, criterion_key], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?))
+    ).map(Some).or_else(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => Ok(None),
+        e => Err(e),
+    })?;
+
+    if let Some((_, c, na, ref obs)) = last {
+        if c == conforme && na == not_applicable && obs == observation {
+            return Ok(None);
+        }
+    }
+    let revision = last.map(|l| l.0).unwrap_or(0) + 1;
+    conn.execute(
+        "INSERT INTO response_revisions (inspection_id, criterion_key, revision, conforme, not_applicable, observation, changed_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![inspection_id, criterion_key, revision, conforme, not_applicable, observation, user_id],
+    )?;
+    Ok(Some(revision))
+}
+
+/// Révisions d'un critère, de la plus ancienne à la plus récente
+pub fn get_history(db: &Database, inspection_id: &str, criterion_key: &str) -> Result<Vec<ResponseRevision>, AppError> {
+    let conn = db.conn.lock()?;
+    let mut stmt = conn.prepare(&format!("{} WHERE r.inspection_id = ?1 AND r.criterion_key = ?2 ORDER BY r.revision", SELECT))?;
+    let list = stmt.query_map(params![inspection_id, criterion_key], from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(list)
+}
+
+fn get_revision(conn: &Connection, inspection_id: &str, criterion_key: &str, revision: u32) -> Result<ResponseRevision, AppError> {
+    conn.query_row(
+        &format!("{} WHERE r.inspection_id = ?1 AND r.criterion_key = ?2 AND r.revision = ?3", SELECT),
+        params![inspection_id, criterion_key, revision], from_row,
+    ).map_err(AppError::missing(format!("Révision {} introuvable", revision)))
+}
+
+/// Compare deux révisions d'un même critère
+pub fn diff_revisions(db: &Database, inspection_id: &str, criterion_key: &str, from: u32, to: u32) -> Result<RevisionDiff, AppError> {
+    let conn = db.conn.lock()?;
+    let from = get_revision(&conn, inspection_id, criterion_key, from)?;
+    let to = get_revision(&conn, inspection_id, criterion_key, to)?;
+    Ok(RevisionDiff {
+        verdict_changed: from.conforme != to.conforme || from.not_applicable != to.not_applicable,
+        observation_changed: from.observation != to.observation,
+        observation: diff_words(&from.observation, &to.observation),
+        from, to,
+    })
+}
+
+/// Différence mot à mot (plus longue sous-suite commune)
+fn diff_words(before: &str, after: &str) -> Vec<TextChange> {
+    let a: Vec<&str> = before.split_whitespace().collect();
+    let b: Vec<&str> = after.split_whitespace().collect();
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut changes: Vec<TextChange> = Vec::new();
+    let mut push = |kind: ChangeKind, word: &str| match changes.last_mut() {
+        Some(last) if last.kind == kind => { last.text.push(' '); last.text.push_str(word); }
+        _ => changes.push(TextChange { kind, text: word.to_string() }),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            push(ChangeKind::Equal, a[i]); i += 1; j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            push(ChangeKind::Removed, a[i]); i += 1;
+        } else {
+            push(ChangeKind::Added, b[j]); j += 1;
+        }
+    }
+    a[i..].iter().for_each(|w| push(ChangeKind::Removed, w));
+    b[j..].iter().for_each(|w| push(ChangeKind::Added, w));
+    changes
+}
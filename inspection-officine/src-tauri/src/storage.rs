@@ -1,15 +1,26 @@
 use rusqlite::params;
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use crate::db::Database;
+use crate::db::{self, Database};
+use crate::error::{AppError, ErrorCode};
+use crate::attachments;
+use crate::establishments;
+use crate::grid::{GridInfo, Severity};
+use crate::grids;
+use crate::revisions;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SavedInspection {
     pub id: String,
     pub grid_id: String,
+    pub grid_version: String,
     pub status: String,
     pub date_inspection: String,
     pub establishment: String,
+    pub establishment_id: Option<String>,
     pub inspection_type: String,
+    pub pre_opening: bool,    // périmètre limité aux critères ►
+    pub parent_id: Option<String>,  // contre-visite : inspection d'origine
     pub inspectors: Vec<String>,
     pub created_by: Option<String>,
     pub created_by_name: Option<String>,
@@ -21,57 +32,307 @@ pub struct SavedInspection {
     pub progress: InspectionProgress,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct InspectionProgress {
+    pub total: u32,           // critères de la grille
+    pub answered: u32,        // conformes + non conformes
+    pub conforme: u32,
+    pub non_conforme: u32,
+    pub not_applicable: u32,  // hors dénominateur de conformité
+    pub sections: Vec<SectionProgress>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SectionProgress {
+    pub section_id: u32,
+    pub title: String,
     pub total: u32,
     pub answered: u32,
     pub conforme: u32,
     pub non_conforme: u32,
+    pub not_applicable: u32,
+}
+
+// ── Cycle de vie d'une inspection ──
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InspectionStatus {
+    Draft,
+    InProgress,
+    Completed,
+    Validated,
+    Archived,
+}
+
+impl InspectionStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InspectionStatus::Draft => "draft",
+            InspectionStatus::InProgress => "in_progress",
+            InspectionStatus::Completed => "completed",
+            InspectionStatus::Validated => "validated",
+            InspectionStatus::Archived => "archived",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InspectionStatus::Draft => "Brouillon",
+            InspectionStatus::InProgress => "En cours",
+            InspectionStatus::Completed => "Terminée",
+            InspectionStatus::Validated => "Validée",
+            InspectionStatus::Archived => "Archivée",
+        }
+    }
+
+    /// Réponses et métadonnées figées (modifiables seulement après réouverture)
+    pub fn is_locked(self) -> bool {
+        matches!(self, InspectionStatus::Completed | InspectionStatus::Validated | InspectionStatus::Archived)
+    }
+
+    /// Suppression interdite
+    pub fn is_permanent(self) -> bool {
+        matches!(self, InspectionStatus::Validated | InspectionStatus::Archived)
+    }
+
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "draft" => Ok(InspectionStatus::Draft),
+            "in_progress" => Ok(InspectionStatus::InProgress),
+            "completed" => Ok(InspectionStatus::Completed),
+            "validated" => Ok(InspectionStatus::Validated),
+            "archived" => Ok(InspectionStatus::Archived),
+            _ => Err(AppError::validation(format!("Statut inconnu : {}", value))),
+        }
+    }
+}
+
+pub struct Transition {
+    pub from: InspectionStatus,
+    pub to: InspectionStatus,
+    pub roles: &'static [&'static str],
+    pub reopen: bool,         // retour en arrière : motif obligatoire
+}
+
+const EDITORS: &[&str] = &["admin", "lead_inspector", "inspector"];
+const LEADS: &[&str] = &["admin", "lead_inspector"];
+
+/// Transitions autorisées : draft → in_progress → completed → validated → archived,
+/// plus la réouverture (completed/validated → in_progress) réservée aux chefs.
+pub const TRANSITIONS: &[Transition] = &[
+    Transition { from: InspectionStatus::Draft, to: InspectionStatus::InProgress, roles: EDITORS, reopen: false },
+    Transition { from: InspectionStatus::InProgress, to: InspectionStatus::Completed, roles: EDITORS, reopen: false },
+    Transition { from: InspectionStatus::Completed, to: InspectionStatus::Validated, roles: LEADS, reopen: false },
+    Transition { from: InspectionStatus::Validated, to: InspectionStatus::Archived, roles: LEADS, reopen: false },
+    Transition { from: InspectionStatus::Completed, to: InspectionStatus::InProgress, roles: LEADS, reopen: true },
+    Transition { from: InspectionStatus::Validated, to: InspectionStatus::InProgress, roles: LEADS, reopen: true },
+];
+
+fn current_status(conn: &rusqlite::Connection, inspection_id: &str) -> Result<InspectionStatus, AppError> {
+    let status: String = conn.query_row(
+        "SELECT status FROM inspections WHERE id = ?1",
+        params![inspection_id], |r| r.get(0)
+    ).map_err(AppError::missing("Inspection non trouvée"))?;
+    InspectionStatus::parse(&status)
+}
+
+/// Refuse toute modification d'une inspection terminée, validée ou archivée
+pub fn ensure_editable(conn: &rusqlite::Connection, inspection_id: &str) -> Result<(), AppError> {
+    let status = current_status(conn, inspection_id)?;
+    if status.is_locked() {
+        return Err(AppError::locked(format!("Inspection {} : modification impossible sans réouverture", status.label().to_lowercase())));
+    }
+    Ok(())
+}
+
+pub fn find_transition(from: InspectionStatus, to: InspectionStatus) -> Result<&'static Transition, AppError> {
+    TRANSITIONS.iter().find(|t| t.from == from && t.to == to)
+        .ok_or_else(|| AppError::forbidden(format!("Transition interdite : {} → {}", from.label(), to.label())))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NonConformities {
+    pub critique: u32,
+    pub majeur: u32,
+    pub mineur: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionScore {
+    pub section_id: u32,
+    pub title: String,
+    pub score: Option<f64>,       // % pondéré, None si rien n'est évalué
+    pub weight_answered: f64,
+    pub weight_conforme: f64,
+    pub non_conformities: NonConformities,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectionScore {
+    pub score: Option<f64>,
+    pub weight_answered: f64,
+    pub weight_conforme: f64,
+    pub non_conformities: NonConformities,
+    pub sections: Vec<SectionScore>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SavedResponse {
-    pub criterion_id: u32,
+    pub criterion_key: String,
+    pub criterion_id: Option<u32>,  // id dans la grille courante (None si la clé n'y figure plus)
     pub conforme: Option<bool>,
+    pub not_applicable: bool,
     pub observation: String,
     pub updated_by: Option<String>,
     pub updated_at: String,
 }
 
+/// Incohérence entre une saisie (ou une ligne en base) et la grille de l'inspection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum ValidationError {
+    InspectionNotFound { inspection_id: String },
+    GridNotFound { grid_id: String, version: Option<String> },
+    UnknownCriterion { inspection_id: String, criterion_key: String },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ValidationError::InspectionNotFound { .. } => write!(f, "Inspection non trouvée"),
+            ValidationError::GridNotFound { grid_id, version: Some(v) } => write!(f, "Grille introuvable : {} v{}", grid_id, v),
+            ValidationError::GridNotFound { grid_id, version: None } => write!(f, "Grille introuvable : {}", grid_id),
+            ValidationError::UnknownCriterion { criterion_key, .. } =>
+                write!(f, "Critère inconnu pour cette inspection : {}", criterion_key),
+        }
+    }
+}
+
+impl From<ValidationError> for AppError {
+    fn from(e: ValidationError) -> Self {
+        let code = match e {
+            ValidationError::InspectionNotFound { .. } | ValidationError::GridNotFound { .. } => ErrorCode::NotFound,
+            ValidationError::UnknownCriterion { .. } => ErrorCode::Validation,
+        };
+        AppError::new(code, e.to_string()).with_details(serde_json::to_string(&e).unwrap_or_default())
+    }
+}
+
+/// Réponse saisie, pour l'enregistrement par lot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseInput {
+    pub criterion_key: String,
+    pub conforme: Option<bool>,
+    #[serde(default)]
+    pub not_applicable: bool,
+    #[serde(default)]
+    pub observation: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchSaveSummary {
+    pub saved: u32,       // réponses modifiées
+    pub unchanged: u32,   // identiques à l'état enregistré
+    pub conforme: u32,
+    pub non_conforme: u32,
+    pub not_applicable: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateInspectionRequest {
     pub grid_id: String,
     pub date_inspection: String,
     pub establishment: String,
+    #[serde(default)]
+    pub establishment_id: Option<String>,  // sinon : retrouvé / créé à partir du nom
     pub inspection_type: String,
     pub inspectors: Vec<String>,
+    #[serde(default)]
+    pub parent_id: Option<String>,  // contre-visite (ignoré en modification)
 }
 
 // ── Créer ──
 
-pub fn create_inspection(db: &Database, req: &CreateInspectionRequest, user_id: &str) -> Result<String, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub fn create_inspection(db: &Database, req: &CreateInspectionRequest, user_id: &str) -> Result<String, AppError> {
+    let conn = db.conn.lock()?;
+    if let Some(ref parent_id) = req.parent_id {
+        return create_follow_up(&conn, parent_id, req, user_id);
+    }
+    let grid = grids::find(&req.grid_id)
+        .ok_or_else(|| ValidationError::GridNotFound { grid_id: req.grid_id.clone(), version: None })?;
     let id = uuid::Uuid::new_v4().to_string();
     let inspectors_json = serde_json::to_string(&req.inspectors).unwrap_or_default();
 
+    let (establishment_id, establishment) = resolve_establishment(&conn, req)?;
+
+    db::snapshot_grid(&conn, &grid)?;
     conn.execute(
-        "INSERT INTO inspections (id, grid_id, status, date_inspection, establishment, inspection_type, inspectors, created_by)
-         VALUES (?1,?2,'draft',?3,?4,?5,?6,?7)",
-        params![id, req.grid_id, req.date_inspection, req.establishment, req.inspection_type, inspectors_json, user_id],
-    ).map_err(|e| format!("Erreur création inspection : {}", e))?;
+        "INSERT INTO inspections (id, grid_id, grid_version, status, date_inspection, establishment, establishment_id, inspection_type, inspectors, created_by)
+         VALUES (?1,?2,?3,'draft',?4,?5,?6,?7,?8,?9)",
+        params![id, req.grid_id, grid.version, req.date_inspection, establishment, establishment_id, req.inspection_type, inspectors_json, user_id],
+    ).map_err(|e| AppError::database("Erreur création inspection", e))?;
 
     Ok(id)
 }
 
+/// Contre-visite : reprend la grille (même version) et l'établissement de
+/// l'inspection d'origine, limitée aux critères non conformes de celle-ci.
+fn create_follow_up(conn: &rusqlite::Connection, parent_id: &str, req: &CreateInspectionRequest, user_id: &str) -> Result<String, AppError> {
+    if !current_status(conn, parent_id)?.is_locked() {
+        return Err(AppError::validation("L'inspection d'origine doit être terminée avant une contre-visite"));
+    }
+    let grid = resolve_grid(conn, parent_id)?;
+    let parent_answers = answers(conn, parent_id)?;
+    let keys: Vec<String> = grid.sections.iter()
+        .flat_map(|s| &s.items)
+        .filter(|c| parent_answers.get(&c.key).is_some_and(|a| !a.not_applicable && a.conforme == Some(false)))
+        .map(|c| c.key.clone())
+        .collect();
+    if keys.is_empty() {
+        return Err(AppError::validation("Aucun écart à revoir dans l'inspection d'origine"));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let inspectors_json = serde_json::to_string(&req.inspectors).unwrap_or_default();
+    let scope_json = serde_json::to_string(&keys).unwrap_or_default();
+    conn.execute(
+        "INSERT INTO inspections (id, grid_id, grid_version, status, date_inspection, establishment, establishment_id,
+            inspection_type, inspectors, created_by, parent_id, criteria_scope)
+         SELECT ?1, grid_id, grid_version, 'draft', ?2, establishment, establishment_id, ?3, ?4, ?5, id, ?6
+         FROM inspections WHERE id = ?7",
+        params![id, req.date_inspection, req.inspection_type, inspectors_json, user_id, scope_json, parent_id],
+    ).map_err(|e| AppError::database("Erreur création contre-visite", e))?;
+    Ok(id)
+}
+
+/// Établissement de l'inspection : id du registre s'il est fourni,
+/// sinon retrouvé (ou créé) à partir du nom saisi.
+fn resolve_establishment(conn: &rusqlite::Connection, req: &CreateInspectionRequest) -> Result<(Option<String>, String), AppError> {
+    if let Some(ref id) = req.establishment_id {
+        let name: String = conn.query_row(
+            "SELECT name FROM establishments WHERE id = ?1 AND active = 1",
+            params![id], |r| r.get(0)
+        ).map_err(AppError::missing("Établissement non trouvé"))?;
+        return Ok((Some(id.clone()), name));
+    }
+    if req.establishment.trim().is_empty() {
+        return Ok((None, String::new()));
+    }
+    let id = establishments::find_or_create(conn, &req.establishment, &req.grid_id)?;
+    let name: String = conn.query_row("SELECT name FROM establishments WHERE id = ?1", params![id], |r| r.get(0))?;
+    Ok((Some(id), name))
+}
+
 // ── Lister ──
 
-pub fn list_inspections(db: &Database, user_id: Option<&str>, status: Option<&str>) -> Result<Vec<SavedInspection>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub fn list_inspections(db: &Database, user_id: Option<&str>, status: Option<&str>, establishment_id: Option<&str>) -> Result<Vec<SavedInspection>, AppError> {
+    let conn = db.conn.lock()?;
 
     let mut sql = String::from(
         "SELECT i.id, i.grid_id, i.status, i.date_inspection, i.establishment, i.inspection_type,
                 i.inspectors, i.created_by, uc.full_name, i.validated_by, uv.full_name,
-                i.validated_at, i.created_at, i.updated_at
+                i.validated_at, i.created_at, i.updated_at, i.grid_version, i.establishment_id, i.parent_id
          FROM inspections i
          LEFT JOIN users uc ON i.created_by = uc.id
          LEFT JOIN users uv ON i.validated_by = uv.id
@@ -86,12 +347,16 @@ pub fn list_inspections(db: &Database, user_id: Option<&str>, status: Option<&st
     }
     if let Some(st) = status {
         sql.push_str(&format!(" AND i.status = ?{}", idx));
-        bind_values.push(Box::new(st.to_string())); // idx += 1;
+        bind_values.push(Box::new(st.to_string())); idx += 1;
+    }
+    if let Some(eid) = establishment_id {
+        sql.push_str(&format!(" AND i.establishment_id = ?{}", idx));
+        bind_values.push(Box::new(eid.to_string())); // idx += 1;
     }
     sql.push_str(" ORDER BY i.updated_at DESC");
 
     let refs: Vec<&dyn rusqlite::types::ToSql> = bind_values.iter().map(|b| b.as_ref()).collect();
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&sql)?;
 
     let inspections = stmt.query_map(refs.as_slice(), |row| {
         let insp_id: String = row.get(0)?;
@@ -101,10 +366,14 @@ pub fn list_inspections(db: &Database, user_id: Option<&str>, status: Option<&st
         Ok(SavedInspection {
             id: insp_id,
             grid_id: row.get(1)?,
+            grid_version: row.get::<_,String>(14).unwrap_or_default(),
             status: row.get(2)?,
             date_inspection: row.get::<_,String>(3).unwrap_or_default(),
             establishment: row.get::<_,String>(4).unwrap_or_default(),
+            establishment_id: row.get(15)?,
             inspection_type: row.get::<_,String>(5).unwrap_or_default(),
+            pre_opening: false,
+            parent_id: row.get(16)?,
             inspectors,
             created_by: row.get(7)?,
             created_by_name: row.get(8)?,
@@ -113,9 +382,9 @@ pub fn list_inspections(db: &Database, user_id: Option<&str>, status: Option<&st
             validated_at: row.get(11)?,
             created_at: row.get(12)?,
             updated_at: row.get(13)?,
-            progress: InspectionProgress { total: 0, answered: 0, conforme: 0, non_conforme: 0 },
+            progress: InspectionProgress::default(),
         })
-    }).map_err(|e| e.to_string())?
+    })?
     .filter_map(|r| r.ok())
     .collect::<Vec<_>>();
 
@@ -124,50 +393,194 @@ pub fn list_inspections(db: &Database, user_id: Option<&str>, status: Option<&st
     for mut insp in inspections {
         let progress = get_progress(&conn, &insp.id);
         insp.progress = progress;
+        insp.pre_opening = is_pre_opening(&insp.inspection_type);
         result.push(insp);
     }
 
     Ok(result)
 }
 
+/// Progression calculée sur les critères de la grille de l'inspection
+/// (les réponses dont la clé n'y figure pas sont ignorées).
 fn get_progress(conn: &rusqlite::Connection, inspection_id: &str) -> InspectionProgress {
-    let answered: u32 = conn.query_row(
-        "SELECT COUNT(*) FROM responses WHERE inspection_id = ?1 AND conforme IS NOT NULL",
-        params![inspection_id], |r| r.get(0)
-    ).unwrap_or(0);
-    let conforme: u32 = conn.query_row(
-        "SELECT COUNT(*) FROM responses WHERE inspection_id = ?1 AND conforme = 1",
-        params![inspection_id], |r| r.get(0)
-    ).unwrap_or(0);
-    let non_conforme: u32 = conn.query_row(
-        "SELECT COUNT(*) FROM responses WHERE inspection_id = ?1 AND conforme = 0",
-        params![inspection_id], |r| r.get(0)
-    ).unwrap_or(0);
-    let total: u32 = conn.query_row(
-        "SELECT COUNT(*) FROM responses WHERE inspection_id = ?1",
-        params![inspection_id], |r| r.get(0)
-    ).unwrap_or(0);
-    InspectionProgress { total: total.max(answered), answered, conforme, non_conforme }
+    let answers = answers(conn, inspection_id).unwrap_or_default();
+    let Some(grid) = inspection_grid(conn, inspection_id) else {
+        return InspectionProgress::default();
+    };
+
+    let mut progress = InspectionProgress::default();
+    for section in &grid.sections {
+        let mut sec = SectionProgress { section_id: section.id, title: section.title.clone(), ..Default::default() };
+        for item in &section.items {
+            sec.total += 1;
+            match answers.get(&item.key) {
+                Some(a) if a.not_applicable => sec.not_applicable += 1,
+                Some(Answer { conforme: Some(true), .. }) => { sec.answered += 1; sec.conforme += 1; }
+                Some(Answer { conforme: Some(false), .. }) => { sec.answered += 1; sec.non_conforme += 1; }
+                _ => {}
+            }
+        }
+        progress.total += sec.total;
+        progress.answered += sec.answered;
+        progress.conforme += sec.conforme;
+        progress.non_conforme += sec.non_conforme;
+        progress.not_applicable += sec.not_applicable;
+        progress.sections.push(sec);
+    }
+    progress
+}
+
+/// Visite de pré-ouverture : seuls les critères ► sont inspectés
+pub fn is_pre_opening(inspection_type: &str) -> bool {
+    let t = inspection_type.trim().to_lowercase().replace('é', "e").replace(['_', ' '], "-");
+    t == "pre-ouverture" || t == "pre-opening"
+}
+
+/// Grille dans la version avec laquelle l'inspection a été créée :
+/// définition figée en base d'abord, pour que l'inspection garde les
+/// critères vus par l'inspecteur même si un fichier ou une grille intégrée
+/// change sans changer de version. À défaut, le registre, dont la version
+/// est alors figée à son tour. Pour une visite de
+/// pré-ouverture, seuls les critères ► sont conservés ; pour une
+/// contre-visite, seuls les critères repris de l'inspection d'origine.
+pub fn resolve_grid(conn: &rusqlite::Connection, inspection_id: &str) -> Result<GridInfo, ValidationError> {
+    let (grid_id, version, inspection_type, scope): (String, Option<String>, Option<String>, Option<String>) = conn.query_row(
+        "SELECT grid_id, grid_version, inspection_type, criteria_scope FROM inspections WHERE id = ?1",
+        params![inspection_id], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?))
+    ).map_err(|_| ValidationError::InspectionNotFound { inspection_id: inspection_id.to_string() })?;
+
+    let grid = match version {
+        None => grids::find(&grid_id),
+        Some(ref version) => conn.query_row(
+            "SELECT definition FROM grid_versions WHERE grid_id = ?1 AND version = ?2",
+            params![grid_id, version], |r| r.get::<_,String>(0)
+        ).ok().and_then(|json| serde_json::from_str(&json).ok()).or_else(|| {
+            let grid = grids::find_version(&grid_id, version)?;
+            db::snapshot_grid(conn, &grid).ok();
+            Some(grid)
+        }),
+    }.ok_or_else(|| ValidationError::GridNotFound { grid_id: grid_id.clone(), version: version.clone() })?;
+
+    let grid = if is_pre_opening(inspection_type.as_deref().unwrap_or("")) { grid.pre_opening_only() } else { grid };
+    match scope.and_then(|json| serde_json::from_str::<Vec<String>>(&json).ok()) {
+        Some(keys) => Ok(grid.restrict_to(&keys)),
+        None => Ok(grid),
+    }
+}
+
+/// Comme `resolve_grid`, sans le détail de l'erreur.
+pub fn inspection_grid(conn: &rusqlite::Connection, inspection_id: &str) -> Option<GridInfo> {
+    resolve_grid(conn, inspection_id).ok()
+}
+
+/// Vérifie que l'inspection existe, que sa grille est disponible et que
+/// le critère en fait partie (dans le périmètre de l'inspection).
+pub fn check_criterion(conn: &rusqlite::Connection, inspection_id: &str, criterion_key: &str) -> Result<GridInfo, ValidationError> {
+    let grid = resolve_grid(conn, inspection_id)?;
+    if grid.criterion_by_key(criterion_key).is_none() {
+        return Err(ValidationError::UnknownCriterion {
+            inspection_id: inspection_id.to_string(), criterion_key: criterion_key.to_string(),
+        });
+    }
+    Ok(grid)
+}
+
+pub fn get_inspection_grid(db: &Database, inspection_id: &str) -> Result<GridInfo, AppError> {
+    let conn = db.conn.lock()?;
+    resolve_grid(&conn, inspection_id).map_err(AppError::from)
+}
+
+struct Answer {
+    conforme: Option<bool>,
+    not_applicable: bool,
+    observation: String,
+}
+
+fn answers(conn: &rusqlite::Connection, inspection_id: &str) -> Result<HashMap<String, Answer>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT criterion_key, conforme, not_applicable, observation FROM responses WHERE inspection_id = ?1"
+    )?;
+    let map = stmt.query_map(params![inspection_id], |row| {
+        let conf_raw: Option<i32> = row.get(1)?;
+        Ok((row.get::<_,String>(0)?, Answer {
+            conforme: conf_raw.map(|v| v != 0),
+            not_applicable: row.get::<_,bool>(2).unwrap_or(false),
+            observation: row.get::<_,String>(3).unwrap_or_default(),
+        }))
+    })?
+    .filter_map(|r| r.ok())
+    .collect();
+    Ok(map)
+}
+
+fn percent(conforme: f64, answered: f64) -> Option<f64> {
+    if answered > 0.0 { Some((conforme / answered * 1000.0).round() / 10.0) } else { None }
+}
+
+// ── Score pondéré ──
+
+pub fn get_score(db: &Database, inspection_id: &str) -> Result<InspectionScore, AppError> {
+    let conn = db.conn.lock()?;
+    let grid = resolve_grid(&conn, inspection_id)?;
+    let answers = answers(&conn, inspection_id)?;
+
+    let mut total = InspectionScore {
+        score: None, weight_answered: 0.0, weight_conforme: 0.0,
+        non_conformities: NonConformities::default(), sections: Vec::new(),
+    };
+    for section in &grid.sections {
+        let mut sec = SectionScore {
+            section_id: section.id, title: section.title.clone(), score: None,
+            weight_answered: 0.0, weight_conforme: 0.0, non_conformities: NonConformities::default(),
+        };
+        for item in &section.items {
+            let Some(conforme) = answers.get(&item.key).and_then(|a| a.conforme) else { continue };
+            let weight = item.effective_weight();
+            sec.weight_answered += weight;
+            if conforme {
+                sec.weight_conforme += weight;
+            } else {
+                match item.severity {
+                    Severity::Critique => sec.non_conformities.critique += 1,
+                    Severity::Majeur => sec.non_conformities.majeur += 1,
+                    Severity::Mineur => sec.non_conformities.mineur += 1,
+                }
+            }
+        }
+        sec.score = percent(sec.weight_conforme, sec.weight_answered);
+        total.weight_answered += sec.weight_answered;
+        total.weight_conforme += sec.weight_conforme;
+        total.non_conformities.critique += sec.non_conformities.critique;
+        total.non_conformities.majeur += sec.non_conformities.majeur;
+        total.non_conformities.mineur += sec.non_conformities.mineur;
+        total.sections.push(sec);
+    }
+    total.score = percent(total.weight_conforme, total.weight_answered);
+    Ok(total)
 }
 
 // ── Charger réponses ──
 
-pub fn get_responses(db: &Database, inspection_id: &str) -> Result<Vec<SavedResponse>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub fn get_responses(db: &Database, inspection_id: &str) -> Result<Vec<SavedResponse>, AppError> {
+    let conn = db.conn.lock()?;
+    let grid = inspection_grid(&conn, inspection_id);
     let mut stmt = conn.prepare(
-        "SELECT criterion_id, conforme, observation, updated_by, updated_at FROM responses WHERE inspection_id = ?1"
-    ).map_err(|e| e.to_string())?;
+        "SELECT criterion_key, conforme, observation, updated_by, updated_at, not_applicable FROM responses WHERE inspection_id = ?1"
+    )?;
 
     let resp = stmt.query_map(params![inspection_id], |row| {
         let conf_raw: Option<i32> = row.get(1)?;
+        let criterion_key: String = row.get(0)?;
         Ok(SavedResponse {
-            criterion_id: row.get::<_,u32>(0)?,
+            criterion_id: grid.as_ref().and_then(|g| g.criterion_by_key(&criterion_key)).map(|c| c.id),
+            criterion_key,
             conforme: conf_raw.map(|v| v != 0),
+            not_applicable: row.get::<_,bool>(5).unwrap_or(false),
             observation: row.get::<_,String>(2).unwrap_or_default(),
             updated_by: row.get(3)?,
             updated_at: row.get::<_,String>(4).unwrap_or_default(),
         })
-    }).map_err(|e| e.to_string())?
+    })?
     .filter_map(|r| r.ok())
     .collect();
 
@@ -176,77 +589,182 @@ pub fn get_responses(db: &Database, inspection_id: &str) -> Result<Vec<SavedResp
 
 // ── Sauvegarder une réponse ──
 
-pub fn save_response(db: &Database, inspection_id: &str, criterion_id: u32, conforme: Option<bool>, observation: &str, user_id: &str) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    let conf_val: Option<i32> = conforme.map(|b| if b { 1 } else { 0 });
+/// `not_applicable` : le critère est sans objet pour l'établissement ;
+/// le verdict est alors ignoré et l'observation sert de justification (obligatoire).
+pub fn save_response(db: &Database, inspection_id: &str, criterion_key: &str, conforme: Option<bool>,
+    not_applicable: bool, observation: &str, user_id: &str) -> Result<(), AppError> {
+    if not_applicable && observation.trim().is_empty() {
+        return Err(AppError::validation("Justification obligatoire pour un critère non applicable"));
+    }
+    let conn = db.conn.lock()?;
+    check_criterion(&conn, inspection_id, criterion_key)?;
+    ensure_editable(&conn, inspection_id)?;
+
+    let tx = conn.unchecked_transaction()?;
+    write_response(&tx, inspection_id, criterion_key, conforme, not_applicable, observation, user_id)?;
+    touch_inspection(&tx, inspection_id);
+    tx.commit().map_err(AppError::from)
+}
 
+/// Enregistre plusieurs réponses en une seule transaction : toutes sont
+/// vérifiées sur la grille de l'inspection avant écriture, et aucune n'est
+/// enregistrée si l'une d'elles est invalide.
+pub fn save_responses(db: &Database, inspection_id: &str, inputs: &[ResponseInput], user_id: &str) -> Result<BatchSaveSummary, AppError> {
+    if inputs.is_empty() {
+        return Err(AppError::validation("Aucune réponse à enregistrer"));
+    }
+    let conn = db.conn.lock()?;
+    let grid = resolve_grid(&conn, inspection_id)?;
+    ensure_editable(&conn, inspection_id)?;
+
+    let mut seen = std::collections::HashSet::new();
+    for input in inputs {
+        if grid.criterion_by_key(&input.criterion_key).is_none() {
+            return Err(ValidationError::UnknownCriterion {
+                inspection_id: inspection_id.to_string(), criterion_key: input.criterion_key.clone(),
+            }.into());
+        }
+        if !seen.insert(input.criterion_key.as_str()) {
+            return Err(AppError::validation(format!("Critère en double dans le lot : {}", input.criterion_key)));
+        }
+        if input.not_applicable && input.observation.trim().is_empty() {
+            return Err(AppError::validation(format!("Justification obligatoire pour le critère non applicable {}", input.criterion_key)));
+        }
+    }
+
+    let mut summary = BatchSaveSummary::default();
+    let tx = conn.unchecked_transaction()?;
+    for input in inputs {
+        let changed = write_response(&tx, inspection_id, &input.criterion_key, input.conforme,
+            input.not_applicable, &input.observation, user_id)?;
+        if changed { summary.saved += 1 } else { summary.unchanged += 1 }
+        match (input.not_applicable, input.conforme) {
+            (true, _) => summary.not_applicable += 1,
+            (false, Some(true)) => summary.conforme += 1,
+            (false, Some(false)) => summary.non_conforme += 1,
+            _ => {}
+        }
+    }
+    if summary.saved > 0 {
+        touch_inspection(&tx, inspection_id);
+    }
+    tx.commit()?;
+    Ok(summary)
+}
+
+/// Écrit une réponse et sa révision ; retourne false si rien n'a changé
+fn write_response(conn: &rusqlite::Connection, inspection_id: &str, criterion_key: &str, conforme: Option<bool>,
+    not_applicable: bool, observation: &str, user_id: &str) -> Result<bool, AppError> {
+    let conf_val: Option<i32> = if not_applicable { None } else { conforme.map(|b| if b { 1 } else { 0 }) };
+    let revision = revisions::record(conn, inspection_id, criterion_key, conf_val, not_applicable, observation, user_id)?;
+    if revision.is_none() {
+        return Ok(false);
+    }
     conn.execute(
-        "INSERT INTO responses (inspection_id, criterion_id, conforme, observation, updated_by)
-         VALUES (?1, ?2, ?3, ?4, ?5)
-         ON CONFLICT(inspection_id, criterion_id)
-         DO UPDATE SET conforme=?3, observation=?4, updated_by=?5, updated_at=datetime('now','localtime')",
-        params![inspection_id, criterion_id, conf_val, observation, user_id],
-    ).map_err(|e| e.to_string())?;
-
-    // Mettre à jour le statut de l'inspection
+        "INSERT INTO responses (inspection_id, criterion_key, conforme, not_applicable, observation, updated_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(inspection_id, criterion_key)
+         DO UPDATE SET conforme=?3, not_applicable=?4, observation=?5, updated_by=?6, updated_at=datetime('now','localtime')",
+        params![inspection_id, criterion_key, conf_val, not_applicable, observation, user_id],
+    )?;
+    Ok(true)
+}
+
+/// Passe l'inspection en cours à la première saisie et met à jour sa date de modification
+fn touch_inspection(conn: &rusqlite::Connection, inspection_id: &str) {
     conn.execute(
         "UPDATE inspections SET status = CASE WHEN status = 'draft' THEN 'in_progress' ELSE status END,
          updated_at = datetime('now','localtime') WHERE id = ?1",
         params![inspection_id],
     ).ok();
-
-    Ok(())
 }
 
 // ── Mettre à jour le meta ──
 
-pub fn update_inspection_meta(db: &Database, inspection_id: &str, req: &CreateInspectionRequest) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub fn update_inspection_meta(db: &Database, inspection_id: &str, req: &CreateInspectionRequest) -> Result<(), AppError> {
+    let conn = db.conn.lock()?;
+    ensure_editable(&conn, inspection_id)?;
+    let (establishment_id, establishment) = resolve_establishment(&conn, req)?;
     let inspectors_json = serde_json::to_string(&req.inspectors).unwrap_or_default();
     conn.execute(
-        "UPDATE inspections SET date_inspection=?1, establishment=?2, inspection_type=?3,
-         inspectors=?4, updated_at=datetime('now','localtime') WHERE id=?5",
-        params![req.date_inspection, req.establishment, req.inspection_type, inspectors_json, inspection_id],
-    ).map_err(|e| e.to_string())?;
+        "UPDATE inspections SET date_inspection=?1, establishment=?2, establishment_id=?3, inspection_type=?4,
+         inspectors=?5, updated_at=datetime('now','localtime') WHERE id=?6",
+        params![req.date_inspection, establishment, establishment_id, req.inspection_type, inspectors_json, inspection_id],
+    )?;
     Ok(())
 }
 
 // ── Changer le statut ──
 
-pub fn set_status(db: &Database, inspection_id: &str, status: &str, user_id: Option<&str>) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    if status == "validated" {
-        conn.execute(
+pub fn get_status(db: &Database, inspection_id: &str) -> Result<InspectionStatus, AppError> {
+    let conn = db.conn.lock()?;
+    current_status(&conn, inspection_id)
+}
+
+/// Applique la transition `from` → `to` (le contrôle des rôles est fait
+/// par l'appelant via `find_transition`). Échoue si le statut n'est plus `from`.
+pub fn set_status(db: &Database, inspection_id: &str, from: InspectionStatus, to: InspectionStatus,
+    user_id: &str, reason: Option<&str>) -> Result<(), AppError> {
+    let transition = find_transition(from, to)?;
+    if transition.reopen && reason.map(str::trim).unwrap_or("").is_empty() {
+        return Err(AppError::validation("Motif obligatoire pour rouvrir une inspection"));
+    }
+
+    let conn = db.conn.lock()?;
+    if to == InspectionStatus::Completed {
+        let progress = get_progress(&conn, inspection_id);
+        let missing = progress.total - progress.answered - progress.not_applicable;
+        if progress.total == 0 || missing > 0 {
+            return Err(AppError::validation(format!("Inspection incomplète : {} critère(s) sans réponse", missing)));
+        }
+    }
+
+    // WHERE status = from : refuse si le statut a changé entre-temps
+    let updated = match to {
+        InspectionStatus::Validated => conn.execute(
             "UPDATE inspections SET status=?1, validated_by=?2, validated_at=datetime('now','localtime'),
-             updated_at=datetime('now','localtime') WHERE id=?3",
-            params![status, user_id, inspection_id],
-        ).map_err(|e| e.to_string())?;
-    } else {
-        conn.execute(
-            "UPDATE inspections SET status=?1, updated_at=datetime('now','localtime') WHERE id=?2",
-            params![status, inspection_id],
-        ).map_err(|e| e.to_string())?;
+             updated_at=datetime('now','localtime') WHERE id=?3 AND status=?4",
+            params![to.as_str(), user_id, inspection_id, from.as_str()],
+        ),
+        _ if transition.reopen => conn.execute(
+            "UPDATE inspections SET status=?1, validated_by=NULL, validated_at=NULL,
+             updated_at=datetime('now','localtime') WHERE id=?2 AND status=?3",
+            params![to.as_str(), inspection_id, from.as_str()],
+        ),
+        _ => conn.execute(
+            "UPDATE inspections SET status=?1, updated_at=datetime('now','localtime') WHERE id=?2 AND status=?3",
+            params![to.as_str(), inspection_id, from.as_str()],
+        ),
+    }?;
+    if updated == 0 {
+        return Err(AppError::conflict("Le statut de l'inspection a été modifié entre-temps"));
     }
     Ok(())
 }
 
 // ── Supprimer ──
 
-pub fn delete_inspection(db: &Database, inspection_id: &str) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM inspections WHERE id = ?1", params![inspection_id])
-        .map_err(|e| e.to_string())?;
+pub fn delete_inspection(db: &Database, inspection_id: &str) -> Result<(), AppError> {
+    let conn = db.conn.lock()?;
+    let status = current_status(&conn, inspection_id)?;
+    if status.is_permanent() {
+        return Err(AppError::locked(format!("Inspection {} : suppression interdite", status.label().to_lowercase())));
+    }
+    // Les lignes suivent par cascade ; les fichiers joints sont supprimés ensuite
+    let hashes = attachments::hashes_for_inspection(&conn, inspection_id);
+    conn.execute("DELETE FROM inspections WHERE id = ?1", params![inspection_id])?;
+    attachments::remove_unreferenced(&conn, &hashes);
     Ok(())
 }
 
 // ── Obtenir une seule inspection ──
 
-pub fn get_inspection(db: &Database, inspection_id: &str) -> Result<SavedInspection, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub fn get_inspection(db: &Database, inspection_id: &str) -> Result<SavedInspection, AppError> {
+    let conn = db.conn.lock()?;
     let mut insp = conn.query_row(
         "SELECT i.id, i.grid_id, i.status, i.date_inspection, i.establishment, i.inspection_type,
                 i.inspectors, i.created_by, uc.full_name, i.validated_by, uv.full_name,
-                i.validated_at, i.created_at, i.updated_at
+                i.validated_at, i.created_at, i.updated_at, i.grid_version, i.establishment_id, i.parent_id
          FROM inspections i
          LEFT JOIN users uc ON i.created_by = uc.id
          LEFT JOIN users uv ON i.validated_by = uv.id
@@ -256,18 +774,330 @@ pub fn get_inspection(db: &Database, inspection_id: &str) -> Result<SavedInspect
             let inspectors_str: String = row.get::<_,String>(6).unwrap_or_default();
             let inspectors: Vec<String> = serde_json::from_str(&inspectors_str).unwrap_or_default();
             Ok(SavedInspection {
-                id: row.get(0)?, grid_id: row.get(1)?, status: row.get(2)?,
+                id: row.get(0)?, grid_id: row.get(1)?,
+                grid_version: row.get::<_,String>(14).unwrap_or_default(), status: row.get(2)?,
                 date_inspection: row.get::<_,String>(3).unwrap_or_default(),
                 establishment: row.get::<_,String>(4).unwrap_or_default(),
+                establishment_id: row.get(15)?,
                 inspection_type: row.get::<_,String>(5).unwrap_or_default(),
+                pre_opening: false, parent_id: row.get(16)?,
                 inspectors, created_by: row.get(7)?, created_by_name: row.get(8)?,
                 validated_by: row.get(9)?, validated_by_name: row.get(10)?,
                 validated_at: row.get(11)?, created_at: row.get(12)?, updated_at: row.get(13)?,
-                progress: InspectionProgress { total: 0, answered: 0, conforme: 0, non_conforme: 0 },
+                progress: InspectionProgress::default(),
             })
         }
-    ).map_err(|_| "Inspection non trouvée".to_string())?;
+    ).map_err(AppError::missing("Inspection non trouvée"))?;
 
     insp.progress = get_progress(&conn, &insp.id);
+    insp.pre_opening = is_pre_opening(&insp.inspection_type);
     Ok(insp)
 }
+
+// ── Comparaison avec l'inspection précédente ──
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeltaKind {
+    NewlyCompliant,       // non conforme → conforme
+    Regressed,            // conforme → non conforme
+    StillNonCompliant,
+    StillCompliant,
+    NotComparable,        // non évalué ou N/A d'un côté
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriterionDelta {
+    pub criterion_key: String,
+    pub criterion_id: u32,
+    pub section_id: u32,
+    pub reference: String,
+    pub description: String,
+    pub previous: Option<bool>,
+    pub current: Option<bool>,
+    pub kind: DeltaKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectionComparison {
+    pub inspection_id: String,
+    pub previous_inspection_id: Option<String>,
+    pub previous_date: Option<String>,
+    pub newly_compliant: u32,
+    pub regressed: u32,
+    pub still_non_compliant: u32,
+    pub deltas: Vec<CriterionDelta>,
+}
+
+/// Inspection terminée la plus récente du même établissement, sur la même
+/// grille, antérieure à celle donnée (hors contre-visites, partielles).
+fn previous_inspection(conn: &rusqlite::Connection, inspection_id: &str) -> Result<Option<(String, String)>, AppError> {
+    conn.query_row(
+        "SELECT p.id, COALESCE(p.date_inspection, '')
+         FROM inspections p JOIN inspections c ON c.id = ?1
+         WHERE p.id != c.id AND p.grid_id = c.grid_id AND p.parent_id IS NULL
+           AND p.establishment_id IS NOT NULL AND p.establishment_id = c.establishment_id
+           AND p.status IN ('completed','validated','archived')
+           AND (COALESCE(p.date_inspection, '') < COALESCE(c.date_inspection, '')
+                OR (COALESCE(p.date_inspection, '') = COALESCE(c.date_inspection, '') AND p.created_at < c.created_at))
+         ORDER BY p.date_inspection DESC, p.created_at DESC LIMIT 1",
+        params![inspection_id], |r| Ok((r.get(0)?, r.get(1)?))
+    ).map(Some).or_else(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => Ok(None),
+        e => Err(e.into()),
+    })
+}
+
+/// Écarts critère par critère (rapprochés par clé stable) entre une
+/// inspection et la précédente du même établissement.
+pub fn compare_with_previous(db: &Database, inspection_id: &str) -> Result<InspectionComparison, AppError> {
+    let conn = db.conn.lock()?;
+    let grid = resolve_grid(&conn, inspection_id)?;
+
+    let mut result = InspectionComparison {
+        inspection_id: inspection_id.to_string(), previous_inspection_id: None, previous_date: None,
+        newly_compliant: 0, regressed: 0, still_non_compliant: 0, deltas: Vec::new(),
+    };
+    let Some((previous_id, previous_date)) = previous_inspection(&conn, inspection_id)? else {
+        return Ok(result);
+    };
+
+    let current = answers(&conn, inspection_id)?;
+    let previous = answers(&conn, &previous_id)?;
+    let verdict = |answers: &HashMap<String, Answer>, key: &str| {
+        answers.get(key).filter(|a| !a.not_applicable).and_then(|a| a.conforme)
+    };
+
+    for section in &grid.sections {
+        for item in &section.items {
+            let before = verdict(&previous, &item.key);
+            let now = verdict(&current, &item.key);
+            let kind = match (before, now) {
+                (Some(false), Some(true)) => { result.newly_compliant += 1; DeltaKind::NewlyCompliant }
+                (Some(true), Some(false)) => { result.regressed += 1; DeltaKind::Regressed }
+                (Some(false), Some(false)) => { result.still_non_compliant += 1; DeltaKind::StillNonCompliant }
+                (Some(true), Some(true)) => DeltaKind::StillCompliant,
+                _ => DeltaKind::NotComparable,
+            };
+            result.deltas.push(CriterionDelta {
+                criterion_key: item.key.clone(), criterion_id: item.id, section_id: section.id,
+                reference: item.reference.clone(), description: item.description.clone(),
+                previous: before, current: now, kind,
+            });
+        }
+    }
+    result.previous_inspection_id = Some(previous_id);
+    result.previous_date = Some(previous_date);
+    Ok(result)
+}
+
+// ── Rapport de contre-visite ──
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowUpFinding {
+    pub criterion_key: String,
+    pub criterion_id: u32,
+    pub section_id: u32,
+    pub reference: String,
+    pub description: String,
+    pub original_observation: String,  // constat de l'inspection d'origine
+    pub conforme: Option<bool>,        // résultat de la contre-visite
+    pub not_applicable: bool,
+    pub observation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowUpReport {
+    pub inspection_id: String,
+    pub parent_id: String,
+    pub parent_date: String,
+    pub resolved: u32,      // écarts levés
+    pub unresolved: u32,    // écarts maintenus
+    pub pending: u32,       // pas encore revus
+    pub findings: Vec<FollowUpFinding>,
+}
+
+/// Constat d'origine et résultat de la contre-visite, critère par critère
+pub fn get_follow_up_report(db: &Database, inspection_id: &str) -> Result<FollowUpReport, AppError> {
+    let conn = db.conn.lock()?;
+    let (parent_id, parent_date): (Option<String>, String) = conn.query_row(
+        "SELECT c.parent_id, COALESCE(p.date_inspection, '')
+         FROM inspections c LEFT JOIN inspections p ON p.id = c.parent_id WHERE c.id = ?1",
+        params![inspection_id], |r| Ok((r.get(0)?, r.get(1)?))
+    ).map_err(AppError::missing("Inspection non trouvée"))?;
+    let parent_id = parent_id.ok_or_else(|| AppError::validation("Cette inspection n'est pas une contre-visite"))?;
+
+    let grid = resolve_grid(&conn, inspection_id)?;
+    let current = answers(&conn, inspection_id)?;
+    let original = answers(&conn, &parent_id)?;
+
+    let mut report = FollowUpReport {
+        inspection_id: inspection_id.to_string(), parent_id, parent_date,
+        resolved: 0, unresolved: 0, pending: 0, findings: Vec::new(),
+    };
+    for section in &grid.sections {
+        for item in &section.items {
+            let answer = current.get(&item.key);
+            let not_applicable = answer.is_some_and(|a| a.not_applicable);
+            let conforme = answer.filter(|a| !a.not_applicable).and_then(|a| a.conforme);
+            match conforme {
+                Some(true) => report.resolved += 1,
+                Some(false) => report.unresolved += 1,
+                None if !not_applicable => report.pending += 1,
+                None => {}
+            }
+            report.findings.push(FollowUpFinding {
+                criterion_key: item.key.clone(), criterion_id: item.id, section_id: section.id,
+                reference: item.reference.clone(), description: item.description.clone(),
+                original_observation: original.get(&item.key).map(|a| a.observation.clone()).unwrap_or_default(),
+                conforme, not_applicable,
+                observation: answer.map(|a| a.observation.clone()).unwrap_or_default(),
+            });
+        }
+    }
+    Ok(report)
+}
+
+// ── Contrôle d'intégrité ──
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    pub table: String,             // responses, corrective_actions, attachments, inspections
+    pub error: ValidationError,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub inspections_checked: u32,
+    pub rows_checked: u32,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+/// Parcourt la base et signale les inspections dont la grille est
+/// introuvable et les lignes rattachées à un critère hors de la grille
+/// de leur inspection (mêmes règles que `check_criterion`).
+pub fn check_integrity(db: &Database) -> Result<IntegrityReport, AppError> {
+    let conn = db.conn.lock()?;
+    let mut report = IntegrityReport::default();
+    let push = |report: &mut IntegrityReport, table: &str, error: ValidationError| {
+        report.issues.push(IntegrityIssue { table: table.to_string(), message: error.to_string(), error });
+    };
+
+    let inspection_ids: Vec<String> = conn.prepare("SELECT id FROM inspections ORDER BY created_at")
+        .and_then(|mut stmt| stmt.query_map([], |r| r.get(0))?.collect())?;
+    let mut grids: HashMap<String, Option<GridInfo>> = HashMap::new();
+    for id in inspection_ids {
+        report.inspections_checked += 1;
+        match resolve_grid(&conn, &id) {
+            Ok(grid) => { grids.insert(id, Some(grid)); }
+            Err(e) => { push(&mut report, "inspections", e); grids.insert(id, None); }
+        }
+    }
+
+    for table in ["responses", "corrective_actions", "attachments"] {
+        let rows: Vec<(String, String)> = conn.prepare(&format!(
+                "SELECT inspection_id, criterion_key FROM {} WHERE criterion_key IS NOT NULL ORDER BY inspection_id", table))
+            .and_then(|mut stmt| stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?.collect())?;
+        for (inspection_id, criterion_key) in rows {
+            report.rows_checked += 1;
+            match grids.get(&inspection_id) {
+                None => push(&mut report, table, ValidationError::InspectionNotFound { inspection_id }),
+                Some(None) => {}  // grille introuvable : déjà signalé sur l'inspection
+                Some(Some(grid)) if grid.criterion_by_key(&criterion_key).is_none() =>
+                    push(&mut report, table, ValidationError::UnknownCriterion { inspection_id, criterion_key }),
+                Some(Some(_)) => {}
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Database, String, String) {
+        let db = Database::open_temp();
+        let user_id = uuid::Uuid::new_v4().to_string();
+        db.conn.lock().unwrap().execute(
+            "INSERT INTO users (id, username, full_name, role, password_hash) VALUES (?1,'chef','Chef','lead_inspector','x')",
+            params![user_id],
+        ).unwrap();
+        let req = CreateInspectionRequest {
+            grid_id: "officine".into(), date_inspection: "2026-03-02".into(),
+            establishment: "Pharmacie du Port".into(), establishment_id: None,
+            inspection_type: "Inspection de routine".into(), inspectors: vec!["Chef".into()], parent_id: None,
+        };
+        let id = create_inspection(&db, &req, &user_id).unwrap();
+        (db, id, user_id)
+    }
+
+    fn answer_all(db: &Database, inspection_id: &str, user_id: &str) {
+        let grid = get_inspection_grid(db, inspection_id).unwrap();
+        let inputs: Vec<ResponseInput> = grid.sections.iter().flat_map(|s| s.items.iter())
+            .map(|c| ResponseInput { criterion_key: c.key.clone(), conforme: Some(true), not_applicable: false, observation: String::new() })
+            .collect();
+        save_responses(db, inspection_id, &inputs, user_id).unwrap();
+    }
+
+    #[test]
+    fn transitions_table() {
+        use InspectionStatus::*;
+        for (from, to) in [(Draft, InProgress), (InProgress, Completed), (Completed, Validated), (Validated, Archived)] {
+            assert!(!find_transition(from, to).unwrap().reopen, "{:?} → {:?}", from, to);
+        }
+        for (from, to) in [(Completed, InProgress), (Validated, InProgress)] {
+            let t = find_transition(from, to).unwrap();
+            assert!(t.reopen);
+            assert!(!t.roles.contains(&"inspector"));
+        }
+        for (from, to) in [(Draft, Completed), (InProgress, Validated), (Archived, InProgress), (Validated, Draft)] {
+            let err = find_transition(from, to).err().unwrap_or_else(|| panic!("{:?} → {:?} acceptée", from, to));
+            assert_eq!(err.code, ErrorCode::Forbidden);
+        }
+    }
+
+    #[test]
+    fn completing_requires_every_answer() {
+        let (db, id, user) = setup();
+        set_status(&db, &id, InspectionStatus::Draft, InspectionStatus::InProgress, &user, None).unwrap();
+
+        let err = set_status(&db, &id, InspectionStatus::InProgress, InspectionStatus::Completed, &user, None).unwrap_err();
+        assert_eq!(err.code, ErrorCode::Validation);
+        assert_eq!(get_status(&db, &id).unwrap(), InspectionStatus::InProgress);
+
+        answer_all(&db, &id, &user);
+        set_status(&db, &id, InspectionStatus::InProgress, InspectionStatus::Completed, &user, None).unwrap();
+        assert_eq!(get_status(&db, &id).unwrap(), InspectionStatus::Completed);
+        assert_eq!(ensure_editable(&db.conn.lock().unwrap(), &id).unwrap_err().code, ErrorCode::Locked);
+    }
+
+    #[test]
+    fn stale_status_is_a_conflict() {
+        let (db, id, user) = setup();
+        set_status(&db, &id, InspectionStatus::Draft, InspectionStatus::InProgress, &user, None).unwrap();
+
+        // Deuxième fenêtre encore sur l'ancien statut
+        let err = set_status(&db, &id, InspectionStatus::Draft, InspectionStatus::InProgress, &user, None).unwrap_err();
+        assert_eq!(err.code, ErrorCode::Conflict);
+        assert_eq!(get_status(&db, &id).unwrap(), InspectionStatus::InProgress);
+    }
+
+    #[test]
+    fn reopening_needs_a_reason_and_clears_validation() {
+        let (db, id, user) = setup();
+        set_status(&db, &id, InspectionStatus::Draft, InspectionStatus::InProgress, &user, None).unwrap();
+        answer_all(&db, &id, &user);
+        set_status(&db, &id, InspectionStatus::InProgress, InspectionStatus::Completed, &user, None).unwrap();
+        set_status(&db, &id, InspectionStatus::Completed, InspectionStatus::Validated, &user, None).unwrap();
+
+        let err = set_status(&db, &id, InspectionStatus::Validated, InspectionStatus::InProgress, &user, Some("  ")).unwrap_err();
+        assert_eq!(err.code, ErrorCode::Validation);
+
+        set_status(&db, &id, InspectionStatus::Validated, InspectionStatus::InProgress, &user, Some("Erreur de saisie")).unwrap();
+        let validated_by: Option<String> = db.conn.lock().unwrap()
+            .query_row("SELECT validated_by FROM inspections WHERE id=?1", params![id], |r| r.get(0)).unwrap();
+        assert_eq!(validated_by, None);
+    }
+}
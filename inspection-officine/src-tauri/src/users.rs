@@ -1,6 +1,11 @@
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::atomic::{AtomicI64, Ordering};
+use crate::config;
 use crate::db::Database;
+use crate::error::{AppError, ErrorCode};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
@@ -9,6 +14,8 @@ pub struct User {
     pub full_name: String,
     pub role: String,
     pub active: bool,
+    pub must_change_password: bool,
+    pub locked_until: Option<String>,  // verrouillage après échecs de connexion
     pub created_at: String,
     pub updated_at: String,
 }
@@ -16,9 +23,21 @@ pub struct User {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionInfo {
     pub token: String,
+    pub session_id: String,
     pub user: User,
 }
 
+/// Session ouverte, telle que présentée aux administrateurs (sans le jeton)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub id: String,
+    pub user_id: String,
+    pub created_at: String,
+    pub last_seen_at: String,
+    pub expires_at: String,
+    pub current: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateUserRequest {
     pub username: String,
@@ -27,6 +46,14 @@ pub struct CreateUserRequest {
     pub password: String,
 }
 
+/// Premier lancement : compte administrateur initial
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitialAdminRequest {
+    pub username: String,
+    pub full_name: String,
+    pub password: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateUserRequest {
     pub full_name: Option<String>,
@@ -34,137 +61,632 @@ pub struct UpdateUserRequest {
     pub active: Option<bool>,
 }
 
+const USER_COLUMNS: &str = "u.id, u.username, u.full_name, u.role, u.active, u.must_change_password,
+    (SELECT a.locked_until FROM login_attempts a WHERE a.username = u.username
+       AND a.locked_until > datetime('now','localtime')),
+    u.created_at, u.updated_at";
+
+fn user_from_row(row: &rusqlite::Row) -> rusqlite::Result<User> {
+    Ok(User {
+        id: row.get(0)?, username: row.get(1)?, full_name: row.get(2)?, role: row.get(3)?,
+        active: row.get(4)?, must_change_password: row.get(5)?, locked_until: row.get(6)?,
+        created_at: row.get(7)?, updated_at: row.get(8)?,
+    })
+}
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// ── Échecs de connexion ──
+
+/// Échecs consécutifs avant verrouillage temporaire du compte
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const LOCKOUT_MINUTES: i64 = 15;
+
+/// Délai imposé avant la tentative suivante : aucun pour le premier
+/// échec, puis 2, 4, 8... secondes (30 au plus).
+fn retry_delay(failed_count: u32) -> chrono::Duration {
+    if failed_count < 2 { return chrono::Duration::zero(); }
+    chrono::Duration::seconds((1i64 << (failed_count - 1).min(5)).min(30))
+}
+
+/// Échec de connexion, avec ce qu'il faut pour le journal d'audit
+#[derive(Debug, Clone)]
+pub struct LoginFailure {
+    pub error: AppError,
+    pub user_id: Option<String>,
+    pub failed_count: u32,
+    pub locked_now: bool,  // cet échec a déclenché le verrouillage
+}
+
+impl From<LoginFailure> for AppError {
+    fn from(f: LoginFailure) -> AppError { f.error }
+}
+
+impl From<AppError> for LoginFailure {
+    fn from(error: AppError) -> LoginFailure {
+        LoginFailure { error, user_id: None, failed_count: 0, locked_now: false }
+    }
+}
+
+fn parse_time(value: &str) -> Option<chrono::NaiveDateTime> {
+    chrono::NaiveDateTime::parse_from_str(value, TIME_FORMAT).ok()
+}
+
+/// Compte un échec pour cet identifiant (connu ou non) et verrouille au-delà du seuil
+fn record_failure(conn: &rusqlite::Connection, username: &str, user_id: Option<String>, previous: u32,
+    now: chrono::NaiveDateTime) -> Result<LoginFailure, AppError> {
+    let failed_count = previous + 1;
+    let locked_until = (failed_count >= MAX_FAILED_ATTEMPTS)
+        .then(|| (now + chrono::Duration::minutes(LOCKOUT_MINUTES)).format(TIME_FORMAT).to_string());
+    conn.execute(
+        "INSERT INTO login_attempts (username, failed_count, last_failed_at, locked_until) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(username) DO UPDATE SET failed_count = ?2, last_failed_at = ?3, locked_until = ?4",
+        params![username, failed_count, now.format(TIME_FORMAT).to_string(), locked_until],
+    )?;
+    let error = match locked_until {
+        Some(_) => AppError::new(ErrorCode::AccountLocked, format!(
+            "Compte verrouillé pendant {} minutes après {} échecs de connexion", LOCKOUT_MINUTES, failed_count)),
+        None => AppError::new(ErrorCode::InvalidCredentials, "Identifiants incorrects"),
+    };
+    Ok(LoginFailure { locked_now: locked_until.is_some(), error, user_id, failed_count })
+}
+
 // ── Authentification ──
 
-pub fn login(db: &Database, username: &str, password: &str) -> Result<SessionInfo, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+pub fn login(db: &Database, username: &str, password: &str) -> Result<SessionInfo, LoginFailure> {
+    let conn = db.conn.lock().map_err(AppError::from)?;
+    let now = chrono::Local::now().naive_local();
+
+    let attempts: Option<(u32, String, Option<String>)> = conn.query_row(
+        "SELECT failed_count, last_failed_at, locked_until FROM login_attempts WHERE username = ?1",
+        params![username], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    ).optional().map_err(AppError::from)?;
+    let mut failed_count = 0;
+    if let Some((count, last_failed_at, locked_until)) = attempts {
+        match locked_until.as_deref().and_then(parse_time) {
+            Some(until) if until > now => return Err(LoginFailure {
+                error: AppError::new(ErrorCode::AccountLocked,
+                    format!("Compte verrouillé jusqu'à {}", until.format("%H:%M"))),
+                user_id: None, failed_count: count, locked_now: false,
+            }),
+            Some(_) => {}  // verrouillage échu : nouveau décompte
+            None => {
+                failed_count = count;
+                let next = parse_time(&last_failed_at).map(|t| t + retry_delay(count));
+                if let Some(next) = next.filter(|next| *next > now) {
+                    return Err(LoginFailure {
+                        error: AppError::new(ErrorCode::TooManyAttempts, format!(
+                            "Trop de tentatives, réessayez dans {} s", ((next - now).num_milliseconds() + 999) / 1000)),
+                        user_id: None, failed_count: count, locked_now: false,
+                    });
+                }
+            }
+        }
+    }
 
-    let result = conn.query_row(
-        "SELECT id, username, full_name, role, active, password_hash, created_at, updated_at FROM users WHERE username = ?1",
+    let found = conn.query_row(
+        &format!("SELECT {}, u.password_hash FROM users u WHERE u.username = ?1", USER_COLUMNS),
         params![username],
-        |row| {
-            Ok((
-                row.get::<_,String>(0)?,
-                row.get::<_,String>(1)?,
-                row.get::<_,String>(2)?,
-                row.get::<_,String>(3)?,
-                row.get::<_,bool>(4)?,
-                row.get::<_,String>(5)?,
-                row.get::<_,String>(6)?,
-                row.get::<_,String>(7)?,
-            ))
-        },
-    ).map_err(|_| "Identifiants incorrects".to_string())?;
-
-    let (id, uname, full_name, role, active, hash, created_at, updated_at) = result;
-
-    if !active {
-        return Err("Compte désactivé".to_string());
+        |row| Ok((user_from_row(row)?, row.get::<_,String>(9)?)),
+    ).optional().map_err(AppError::from)?;
+    let Some((mut user, hash)) = found else {
+        return Err(record_failure(&conn, username, None, failed_count, now)?);
+    };
+
+    if !user.active {
+        return Err(AppError::new(ErrorCode::AccountDisabled, "Compte désactivé").into());
     }
 
     if !bcrypt::verify(password, &hash).unwrap_or(false) {
-        return Err("Identifiants incorrects".to_string());
+        return Err(record_failure(&conn, username, Some(user.id), failed_count, now)?);
+    }
+    conn.execute("DELETE FROM login_attempts WHERE username = ?1", params![username]).map_err(AppError::from)?;
+    upgrade_hash(&conn, &user.id, password, &hash);
+    if !user.must_change_password && password_expired(&conn, &user.id).map_err(AppError::from)? {
+        user.must_change_password = true;
     }
 
     let token = uuid::Uuid::new_v4().to_string();
-    let expires = chrono::Local::now()
-        .checked_add_signed(chrono::Duration::hours(24))
-        .unwrap()
-        .format("%Y-%m-%d %H:%M:%S")
+    let expires = (now + chrono::Duration::hours(config::get().session.max_hours as i64))
+        .format(TIME_FORMAT)
         .to_string();
 
+    let session_id = uuid::Uuid::new_v4().to_string();
     conn.execute(
-        "INSERT INTO sessions (token, user_id, expires_at) VALUES (?1, ?2, ?3)",
-        params![token, id, expires],
-    ).map_err(|e| e.to_string())?;
+        "INSERT INTO sessions (id, token_hash, user_id, expires_at) VALUES (?1, ?2, ?3, ?4)",
+        params![session_id, token_hash(db, &token), user.id, expires],
+    ).map_err(AppError::from)?;
 
-    Ok(SessionInfo {
-        token: token.clone(),
-        user: User { id, username: uname, full_name, role, active, created_at, updated_at },
-    })
+    Ok(SessionInfo { token, session_id, user })
+}
+
+// ── Sessions ──
+
+/// Empreinte HMAC-SHA256 du jeton : seule valeur stockée en base, une copie
+/// du fichier ne suffit donc pas à reprendre une session ouverte
+fn token_hash(db: &Database, token: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&db.session_key).expect("HMAC accepte toute longueur de clé");
+    mac.update(token.as_bytes());
+    mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Intervalle minimal entre deux purges des sessions expirées
+const PURGE_INTERVAL_SECS: i64 = 600;
+static LAST_PURGE: AtomicI64 = AtomicI64::new(0);
+
+/// Condition SQL d'une session encore valide : ni sa durée maximale ni le
+/// délai d'inactivité (paramètre `:idle`, ex. "-30 minutes") dépassés
+const SESSION_ALIVE: &str = "s.expires_at > datetime('now','localtime')
+    AND s.last_seen_at > datetime('now','localtime', :idle)";
+
+fn idle_modifier() -> String {
+    format!("-{} minutes", config::get().session.idle_minutes)
+}
+
+/// Supprime les sessions expirées, au plus une fois par intervalle
+fn purge_expired_sessions(conn: &rusqlite::Connection) {
+    let now = chrono::Local::now().timestamp();
+    let last = LAST_PURGE.load(Ordering::Relaxed);
+    if now - last < PURGE_INTERVAL_SECS
+        || LAST_PURGE.compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed).is_err() {
+        return;
+    }
+    conn.execute(
+        &format!("DELETE FROM sessions AS s WHERE NOT ({})", SESSION_ALIVE),
+        rusqlite::named_params! { ":idle": idle_modifier() },
+    ).ok();
+}
+
+/// Utilisateur d'une session valide, qu'il ait ou non à changer son mot
+/// de passe (changement de mot de passe, déconnexion). Chaque appel compte
+/// comme une activité et repousse l'expiration par inactivité.
+pub fn session_user(db: &Database, token: &str) -> Result<User, AppError> {
+    let conn = db.conn.lock()?;
+    purge_expired_sessions(&conn);
+    let (session_id, user): (String, User) = conn.query_row(
+        &format!("SELECT {}, s.id FROM sessions s JOIN users u ON s.user_id = u.id
+         WHERE s.token_hash = :token_hash AND {} AND u.active = 1", USER_COLUMNS, SESSION_ALIVE),
+        rusqlite::named_params! { ":token_hash": token_hash(db, token), ":idle": idle_modifier() },
+        |row| Ok((row.get(9)?, user_from_row(row)?)),
+    ).map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => AppError::session_expired(),
+        e => AppError::from(e),
+    })?;
+    conn.execute("UPDATE sessions SET last_seen_at = datetime('now','localtime') WHERE id = ?1",
+        params![session_id])?;
+    Ok(user)
+}
+
+/// Utilisateur d'une session valide, refusée tant qu'un changement de mot
+/// de passe est imposé (mot de passe expiré ou réinitialisé par un admin)
+pub fn validate_session(db: &Database, token: &str) -> Result<User, AppError> {
+    let user = session_user(db, token)?;
+    if user.must_change_password {
+        return Err(AppError::new(ErrorCode::PasswordChangeRequired,
+            "Vous devez changer votre mot de passe avant de continuer"));
+    }
+    Ok(user)
+}
+
+/// Ferme la session ; renvoie son identifiant si elle existait
+pub fn logout(db: &Database, token: &str) -> Result<Option<String>, AppError> {
+    let conn = db.conn.lock()?;
+    Ok(conn.query_row("DELETE FROM sessions WHERE token_hash = ?1 RETURNING id",
+        params![token_hash(db, token)], |r| r.get(0)).optional()?)
 }
 
-pub fn validate_session(db: &Database, token: &str) -> Result<User, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    conn.query_row(
-        "SELECT u.id, u.username, u.full_name, u.role, u.active, u.created_at, u.updated_at
-         FROM sessions s JOIN users u ON s.user_id = u.id
-         WHERE s.token = ?1 AND s.expires_at > datetime('now','localtime') AND u.active = 1",
-        params![token],
-        |row| Ok(User {
-            id: row.get(0)?, username: row.get(1)?, full_name: row.get(2)?,
-            role: row.get(3)?, active: row.get(4)?, created_at: row.get(5)?, updated_at: row.get(6)?,
+/// Sessions en cours d'un utilisateur ; `current` signale celle du demandeur
+pub fn list_sessions(db: &Database, user_id: &str, current_token: &str) -> Result<Vec<SessionSummary>, AppError> {
+    let conn = db.conn.lock()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT s.id, s.user_id, s.created_at, s.last_seen_at, s.expires_at, s.token_hash = :current
+         FROM sessions s WHERE s.user_id = :user_id AND {} ORDER BY s.last_seen_at DESC", SESSION_ALIVE))?;
+    let sessions = stmt.query_map(
+        rusqlite::named_params! { ":user_id": user_id, ":current": token_hash(db, current_token), ":idle": idle_modifier() },
+        |row| Ok(SessionSummary {
+            id: row.get(0)?, user_id: row.get(1)?, created_at: row.get(2)?,
+            last_seen_at: row.get(3)?, expires_at: row.get(4)?, current: row.get(5)?,
         }),
-    ).map_err(|_| "Session invalide ou expirée".to_string())
+    )?
+    .filter_map(|r| r.ok())
+    .collect();
+    Ok(sessions)
+}
+
+/// Ferme une session par son identifiant ; renvoie l'utilisateur concerné
+pub fn revoke_session(db: &Database, session_id: &str) -> Result<String, AppError> {
+    let conn = db.conn.lock()?;
+    let user_id: String = conn.query_row("SELECT user_id FROM sessions WHERE id = ?1", params![session_id], |r| r.get(0))
+        .map_err(AppError::missing("Session introuvable ou déjà fermée"))?;
+    conn.execute("DELETE FROM sessions WHERE id = ?1", params![session_id])?;
+    Ok(user_id)
+}
+
+/// Ferme toutes les sessions d'un utilisateur ; renvoie leur nombre
+pub fn revoke_user_sessions(db: &Database, user_id: &str) -> Result<usize, AppError> {
+    let conn = db.conn.lock()?;
+    revoke_sessions(&conn, user_id, None)
 }
 
-pub fn logout(db: &Database, token: &str) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM sessions WHERE token = ?1", params![token])
-        .map_err(|e| e.to_string())?;
+/// Ferme les sessions d'un utilisateur, sauf éventuellement celle du
+/// demandeur (`keep`, empreinte de son jeton)
+fn revoke_sessions(conn: &rusqlite::Connection, user_id: &str, keep: Option<&str>) -> Result<usize, AppError> {
+    Ok(conn.execute("DELETE FROM sessions WHERE user_id = ?1 AND token_hash IS NOT ?2", params![user_id, keep])?)
+}
+
+// ── Mots de passe ──
+
+/// Refuse un mot de passe contraire à la politique ; pour un utilisateur
+/// existant, refuse aussi la réutilisation de ses derniers mots de passe.
+fn check_password(conn: &rusqlite::Connection, user_id: Option<&str>, password: &str) -> Result<(), AppError> {
+    let policy = &config::get().password;
+    let missing = missing_requirements(policy, password);
+    if !missing.is_empty() {
+        return Err(AppError::validation(format!("Mot de passe trop faible : {}", missing.join(", "))));
+    }
+
+    if let (Some(user_id), true) = (user_id, policy.history > 0) {
+        let mut stmt = conn.prepare(
+            "SELECT password_hash FROM password_history WHERE user_id = ?1 ORDER BY id DESC LIMIT ?2")?;
+        let hashes = stmt.query_map(params![user_id, policy.history], |r| r.get::<_,String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if hashes.iter().any(|h| bcrypt::verify(password, h).unwrap_or(false)) {
+            return Err(AppError::validation(format!(
+                "Mot de passe déjà utilisé : les {} derniers ne peuvent pas être repris", policy.history)));
+        }
+    }
     Ok(())
 }
 
+/// Exigences de la politique que le mot de passe ne remplit pas
+fn missing_requirements(policy: &config::PasswordPolicy, password: &str) -> Vec<String> {
+    let mut missing = Vec::new();
+    if password.chars().count() < policy.min_length {
+        missing.push(format!("au moins {} caractères", policy.min_length));
+    }
+    if policy.require_upper && !password.chars().any(char::is_uppercase) { missing.push("une majuscule".to_string()); }
+    if policy.require_lower && !password.chars().any(char::is_lowercase) { missing.push("une minuscule".to_string()); }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) { missing.push("un chiffre".to_string()); }
+    if policy.require_symbol && !password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+        missing.push("un caractère spécial".to_string());
+    }
+    missing
+}
+
+/// Ajoute le mot de passe à l'historique et n'en garde que ce qu'exige la politique
+fn record_history(conn: &rusqlite::Connection, user_id: &str, hash: &str) -> Result<(), AppError> {
+    conn.execute("INSERT INTO password_history (user_id, password_hash) VALUES (?1, ?2)", params![user_id, hash])?;
+    conn.execute(
+        "DELETE FROM password_history WHERE user_id = ?1 AND id NOT IN (
+             SELECT id FROM password_history WHERE user_id = ?1 ORDER BY id DESC LIMIT ?2)",
+        params![user_id, config::get().password.history],
+    )?;
+    Ok(())
+}
+
+/// Mot de passe plus ancien que la durée maximale : changement imposé
+fn password_expired(conn: &rusqlite::Connection, user_id: &str) -> rusqlite::Result<bool> {
+    let max_age_days = config::get().password.max_age_days;
+    if max_age_days == 0 {
+        return Ok(false);
+    }
+    let expired = conn.execute(
+        "UPDATE users SET must_change_password = 1 WHERE id = ?1
+         AND COALESCE(password_changed_at, created_at) < datetime('now','localtime',?2)",
+        params![user_id, format!("-{} days", max_age_days)],
+    )?;
+    Ok(expired > 0)
+}
+
+/// Empreinte calculée avec un coût inférieur à la configuration : recalculée
+/// à la connexion, tant que le mot de passe en clair est disponible.
+fn upgrade_hash(conn: &rusqlite::Connection, user_id: &str, password: &str, hash: &str) {
+    let cost = config::get().security.bcrypt_cost;
+    let current: Option<u32> = hash.split('$').nth(2).and_then(|c| c.parse().ok());
+    if current.is_some_and(|c| c < cost) {
+        if let Ok(rehashed) = bcrypt::hash(password, cost) {
+            conn.execute("UPDATE users SET password_hash = ?1 WHERE id = ?2", params![rehashed, user_id]).ok();
+        }
+    }
+}
+
 // ── CRUD Utilisateurs ──
 
-pub fn create_user(db: &Database, req: &CreateUserRequest) -> Result<User, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+const ROLES: [&str; 4] = ["admin", "lead_inspector", "inspector", "viewer"];
+
+fn validate_role(role: &str) -> Result<(), AppError> {
+    if ROLES.contains(&role) { Ok(()) } else { Err(AppError::validation(format!("Rôle inconnu : {}", role))) }
+}
+
+pub fn create_user(db: &Database, req: &CreateUserRequest) -> Result<User, AppError> {
+    validate_role(&req.role)?;
+    let conn = db.conn.lock()?;
+    insert_user(&conn, &req.username, &req.full_name, &req.role, &req.password)
+}
+
+fn insert_user(conn: &rusqlite::Connection, username: &str, full_name: &str, role: &str, password: &str) -> Result<User, AppError> {
+    check_password(conn, None, password)?;
     let id = uuid::Uuid::new_v4().to_string();
-    let hash = bcrypt::hash(&req.password, 8).map_err(|e| e.to_string())?;
+    let hash = bcrypt::hash(password, config::get().security.bcrypt_cost)?;
 
     conn.execute(
-        "INSERT INTO users (id, username, full_name, role, password_hash) VALUES (?1,?2,?3,?4,?5)",
-        params![id, req.username, req.full_name, req.role, hash],
-    ).map_err(|e| format!("Erreur création : {}", e))?;
+        "INSERT INTO users (id, username, full_name, role, password_hash, password_changed_at)
+         VALUES (?1,?2,?3,?4,?5,datetime('now','localtime'))",
+        params![id, username, full_name, role, hash],
+    ).map_err(|e| match AppError::from(e) {
+        e if e.code == ErrorCode::Conflict => AppError::conflict(format!("Identifiant déjà utilisé : {}", username)),
+        e => e,
+    })?;
+    record_history(conn, &id, &hash)?;
 
     let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    Ok(User { id, username: req.username.clone(), full_name: req.full_name.clone(),
-              role: req.role.clone(), active: true, created_at: now.clone(), updated_at: now })
+    Ok(User { id, username: username.to_string(), full_name: full_name.to_string(), role: role.to_string(),
+              active: true, must_change_password: false, locked_until: None, created_at: now.clone(), updated_at: now })
 }
 
-pub fn update_user(db: &Database, user_id: &str, req: &UpdateUserRequest) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+// ── Premier lancement ──
+
+fn admin_exists(conn: &rusqlite::Connection) -> rusqlite::Result<bool> {
+    conn.query_row("SELECT COUNT(*) > 0 FROM users WHERE role = 'admin'", [], |r| r.get(0))
+}
+
+/// Vrai tant qu'aucun administrateur n'a été créé
+pub fn needs_setup(db: &Database) -> Result<bool, AppError> {
+    let conn = db.conn.lock()?;
+    Ok(!admin_exists(&conn)?)
+}
+
+/// Crée l'administrateur initial ; refusé dès qu'un administrateur existe
+pub fn create_initial_admin(db: &Database, req: &InitialAdminRequest) -> Result<User, AppError> {
+    if req.username.trim().is_empty() || req.full_name.trim().is_empty() {
+        return Err(AppError::validation("Identifiant et nom complet obligatoires"));
+    }
+    let conn = db.conn.lock()?;
+    let tx = conn.unchecked_transaction()?;
+    if admin_exists(&tx)? {
+        return Err(AppError::forbidden("L'application est déjà configurée"));
+    }
+    let user = insert_user(&tx, req.username.trim(), req.full_name.trim(), "admin", &req.password)?;
+    tx.commit()?;
+    Ok(user)
+}
+
+/// Refuse une modification qui ne laisserait aucun administrateur actif :
+/// l'écran de premier lancement redeviendrait accessible sans authentification.
+/// À appeler dans la transaction de la modification, avant validation.
+fn ensure_active_admin(conn: &rusqlite::Connection) -> Result<(), AppError> {
+    let admins: u32 = conn.query_row("SELECT COUNT(*) FROM users WHERE role = 'admin' AND active = 1", [], |r| r.get(0))?;
+    if admins == 0 {
+        return Err(AppError::conflict("Impossible de retirer le dernier administrateur actif"));
+    }
+    Ok(())
+}
+
+pub fn update_user(db: &Database, user_id: &str, req: &UpdateUserRequest) -> Result<(), AppError> {
+    let conn = db.conn.lock()?;
+    let tx = conn.unchecked_transaction()?;
     if let Some(ref name) = req.full_name {
-        conn.execute("UPDATE users SET full_name=?1, updated_at=datetime('now','localtime') WHERE id=?2",
-            params![name, user_id]).map_err(|e| e.to_string())?;
+        tx.execute("UPDATE users SET full_name=?1, updated_at=datetime('now','localtime') WHERE id=?2",
+            params![name, user_id])?;
     }
     if let Some(ref role) = req.role {
-        conn.execute("UPDATE users SET role=?1, updated_at=datetime('now','localtime') WHERE id=?2",
-            params![role, user_id]).map_err(|e| e.to_string())?;
+        validate_role(role)?;
+        // Un changement de rôle ferme les sessions ouvertes avec l'ancien
+        let changed = tx.execute("UPDATE users SET role=?1, updated_at=datetime('now','localtime')
+            WHERE id=?2 AND role <> ?1", params![role, user_id])?;
+        if changed > 0 {
+            revoke_sessions(&tx, user_id, None)?;
+        }
     }
     if let Some(active) = req.active {
-        conn.execute("UPDATE users SET active=?1, updated_at=datetime('now','localtime') WHERE id=?2",
-            params![active, user_id]).map_err(|e| e.to_string())?;
+        tx.execute("UPDATE users SET active=?1, updated_at=datetime('now','localtime') WHERE id=?2",
+            params![active, user_id])?;
+        if !active {
+            revoke_sessions(&tx, user_id, None)?;
+        }
     }
+    ensure_active_admin(&tx)?;
+    tx.commit()?;
     Ok(())
 }
 
-pub fn change_password(db: &Database, user_id: &str, new_password: &str) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    let hash = bcrypt::hash(new_password, 8).map_err(|e| e.to_string())?;
-    conn.execute("UPDATE users SET password_hash=?1, updated_at=datetime('now','localtime') WHERE id=?2",
-        params![hash, user_id]).map_err(|e| e.to_string())?;
+/// Nouveau mot de passe fixé par un administrateur. Sauf pour son propre
+/// compte (`must_change` à faux), l'utilisateur devra le changer à la
+/// prochaine utilisation. Les sessions de l'utilisateur sont fermées, hormis
+/// celle de l'administrateur (`admin_token`).
+pub fn change_password(db: &Database, user_id: &str, new_password: &str, must_change: bool, admin_token: &str) -> Result<(), AppError> {
+    let conn = db.conn.lock()?;
+    set_password(&conn, user_id, new_password, must_change, Some(&token_hash(db, admin_token)))
+}
+
+/// Changement par l'utilisateur lui-même, sur présentation du mot de passe
+/// actuel. Ses autres sessions sont fermées, la session courante (`token`)
+/// reste ouverte.
+pub fn change_own_password(db: &Database, user_id: &str, token: &str, current_password: &str, new_password: &str) -> Result<(), AppError> {
+    let conn = db.conn.lock()?;
+    let hash: String = conn.query_row("SELECT password_hash FROM users WHERE id = ?1", params![user_id], |r| r.get(0))
+        .map_err(AppError::missing("Utilisateur non trouvé"))?;
+    if !bcrypt::verify(current_password, &hash).unwrap_or(false) {
+        return Err(AppError::new(ErrorCode::InvalidCredentials, "Mot de passe actuel incorrect"));
+    }
+    if current_password == new_password {
+        return Err(AppError::validation("Le nouveau mot de passe doit être différent de l'actuel"));
+    }
+    set_password(&conn, user_id, new_password, false, Some(&token_hash(db, token)))
+}
+
+fn set_password(conn: &rusqlite::Connection, user_id: &str, new_password: &str, must_change: bool,
+    keep: Option<&str>) -> Result<(), AppError> {
+    check_password(conn, Some(user_id), new_password)?;
+    let hash = bcrypt::hash(new_password, config::get().security.bcrypt_cost)?;
+    let updated = conn.execute("UPDATE users SET password_hash=?1, password_changed_at=datetime('now','localtime'),
+        must_change_password=?2, updated_at=datetime('now','localtime') WHERE id=?3",
+        params![hash, must_change, user_id])?;
+    if updated == 0 {
+        return Err(AppError::not_found("Utilisateur non trouvé"));
+    }
+    record_history(conn, user_id, &hash)?;
+    revoke_sessions(conn, user_id, keep)?;
     Ok(())
 }
 
-pub fn list_users(db: &Database) -> Result<Vec<User>, String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    let mut stmt = conn.prepare(
-        "SELECT id, username, full_name, role, active, created_at, updated_at FROM users ORDER BY created_at"
-    ).map_err(|e| e.to_string())?;
-    let users = stmt.query_map([], |row| Ok(User {
-        id: row.get(0)?, username: row.get(1)?, full_name: row.get(2)?,
-        role: row.get(3)?, active: row.get(4)?, created_at: row.get(5)?, updated_at: row.get(6)?,
-    })).map_err(|e| e.to_string())?
+pub fn list_users(db: &Database) -> Result<Vec<User>, AppError> {
+    let conn = db.conn.lock()?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM users u ORDER BY u.created_at", USER_COLUMNS))?;
+    let users = stmt.query_map([], user_from_row)?
     .filter_map(|r| r.ok())
     .collect();
     Ok(users)
 }
 
-pub fn delete_user(db: &Database, user_id: &str) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM sessions WHERE user_id = ?1", params![user_id]).ok();
-    conn.execute("UPDATE users SET active = 0, updated_at=datetime('now','localtime') WHERE id = ?1",
-        params![user_id]).map_err(|e| e.to_string())?;
+pub fn delete_user(db: &Database, user_id: &str) -> Result<(), AppError> {
+    let conn = db.conn.lock()?;
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("UPDATE users SET active = 0, updated_at=datetime('now','localtime') WHERE id = ?1",
+        params![user_id])?;
+    ensure_active_admin(&tx)?;
+    revoke_sessions(&tx, user_id, None)?;
+    tx.commit()?;
     Ok(())
 }
+
+/// Lève le verrouillage et remet à zéro les échecs de connexion
+pub fn unlock_user(db: &Database, user_id: &str) -> Result<(), AppError> {
+    let conn = db.conn.lock()?;
+    conn.execute(
+        "DELETE FROM login_attempts WHERE username = (SELECT username FROM users WHERE id = ?1)",
+        params![user_id],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &str = "Officine2026";
+
+    fn setup() -> (Database, User) {
+        let db = Database::open_temp();
+        let user = create_user(&db, &CreateUserRequest {
+            username: "inspecteur1".into(), full_name: "Inspecteur".into(),
+            role: "inspector".into(), password: PASSWORD.into(),
+        }).unwrap();
+        (db, user)
+    }
+
+    /// Recule le dernier échec d'une heure : le délai entre tentatives est écoulé
+    fn skip_delay(db: &Database, username: &str) {
+        db.conn.lock().unwrap().execute(
+            "UPDATE login_attempts SET last_failed_at = datetime('now','localtime','-1 hour') WHERE username = ?1",
+            params![username],
+        ).unwrap();
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_thirty_seconds() {
+        let delays: Vec<i64> = (0..=8).map(|n| retry_delay(n).num_seconds()).collect();
+        assert_eq!(delays, [0, 0, 2, 4, 8, 16, 30, 30, 30]);
+    }
+
+    #[test]
+    fn delay_is_enforced_between_attempts() {
+        let (db, user) = setup();
+        for _ in 0..2 {
+            let failure = login(&db, &user.username, "mauvais").unwrap_err();
+            assert_eq!(failure.error.code, ErrorCode::InvalidCredentials);
+        }
+        let failure = login(&db, &user.username, PASSWORD).unwrap_err();
+        assert_eq!(failure.error.code, ErrorCode::TooManyAttempts);
+
+        skip_delay(&db, &user.username);
+        login(&db, &user.username, PASSWORD).unwrap();
+        let remaining: u32 = db.conn.lock().unwrap()
+            .query_row("SELECT COUNT(*) FROM login_attempts", [], |r| r.get(0)).unwrap();
+        assert_eq!(remaining, 0, "une connexion réussie remet le décompte à zéro");
+    }
+
+    #[test]
+    fn account_locks_at_threshold_until_expiry() {
+        let (db, user) = setup();
+        for attempt in 1..=MAX_FAILED_ATTEMPTS {
+            skip_delay(&db, &user.username);
+            let failure = login(&db, &user.username, "mauvais").unwrap_err();
+            assert_eq!(failure.failed_count, attempt);
+            assert_eq!(failure.locked_now, attempt == MAX_FAILED_ATTEMPTS);
+        }
+
+        // Verrouillé : même le bon mot de passe est refusé
+        skip_delay(&db, &user.username);
+        let failure = login(&db, &user.username, PASSWORD).unwrap_err();
+        assert_eq!(failure.error.code, ErrorCode::AccountLocked);
+        assert!(!failure.locked_now);
+
+        db.conn.lock().unwrap().execute(
+            "UPDATE login_attempts SET locked_until = datetime('now','localtime','-1 minute')", [],
+        ).unwrap();
+        login(&db, &user.username, PASSWORD).unwrap();
+    }
+
+    #[test]
+    fn unknown_usernames_are_locked_too() {
+        let db = Database::open_temp();
+        for _ in 0..MAX_FAILED_ATTEMPTS {
+            skip_delay(&db, "inconnu");
+            login(&db, "inconnu", "mauvais").unwrap_err();
+        }
+        skip_delay(&db, "inconnu");
+        assert_eq!(login(&db, "inconnu", "mauvais").unwrap_err().error.code, ErrorCode::AccountLocked);
+    }
+
+    #[test]
+    fn each_policy_rule_is_checked() {
+        let policy = config::PasswordPolicy { require_symbol: true, ..Default::default() };
+        assert!(missing_requirements(&policy, "Officine#2026").is_empty());
+        for (password, rule) in [
+            ("Of#2026", "au moins 10 caractères"),
+            ("officine#2026", "une majuscule"),
+            ("OFFICINE#2026", "une minuscule"),
+            ("Officine#abcd", "un chiffre"),
+            ("Officine2026", "un caractère spécial"),
+        ] {
+            assert_eq!(missing_requirements(&policy, password), [rule], "{}", password);
+        }
+
+        let relaxed = config::PasswordPolicy {
+            min_length: 4, require_upper: false, require_lower: false, require_digit: false, require_symbol: false,
+            ..Default::default()
+        };
+        assert!(missing_requirements(&relaxed, "abcd").is_empty());
+        assert_eq!(missing_requirements(&relaxed, "abc").len(), 1);
+    }
+
+    #[test]
+    fn weak_password_is_refused_on_creation() {
+        let db = Database::open_temp();
+        let err = create_user(&db, &CreateUserRequest {
+            username: "faible".into(), full_name: "Faible".into(), role: "viewer".into(), password: "court".into(),
+        }).unwrap_err();
+        assert_eq!(err.code, ErrorCode::Validation);
+    }
+
+    #[test]
+    fn recent_passwords_cannot_be_reused() {
+        let (db, user) = setup();
+        let history = config::get().password.history;
+        let change = |password: &str| {
+            let conn = db.conn.lock().unwrap();
+            set_password(&conn, &user.id, password, false, None)
+        };
+
+        assert_eq!(change(PASSWORD).unwrap_err().code, ErrorCode::Validation);
+        for n in 1..history {
+            change(&format!("Officine{}", 3000 + n)).unwrap();
+        }
+        // Encore parmi les `history` derniers
+        assert_eq!(change(PASSWORD).unwrap_err().code, ErrorCode::Validation);
+
+        change("Officine4000").unwrap();
+        change(PASSWORD).unwrap();
+    }
+}
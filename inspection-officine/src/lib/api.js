@@ -209,8 +209,8 @@ export function fallbackInvoke(cmd, args = {}) {
 
     case 'cmd_get_responses': {
       const r = DB.responses[args.inspectionId] || {};
-      return Object.entries(r).map(([cid, v]) => ({
-        criterion_id: parseInt(cid),
+      return Object.entries(r).map(([key, v]) => ({
+        criterion_key: key,
         conforme: v.conforme,
         observation: v.observation || '',
         updated_by: v.updated_by,
@@ -220,7 +220,7 @@ export function fallbackInvoke(cmd, args = {}) {
 
     case 'cmd_save_response': {
       if (!DB.responses[args.inspectionId]) DB.responses[args.inspectionId] = {};
-      DB.responses[args.inspectionId][args.criterionId] = {
+      DB.responses[args.inspectionId][args.criterionKey] = {
         conforme: args.conforme,
         observation: args.observation,
         updated_by: args.session?.user?.id,
@@ -38,7 +38,7 @@ export async function getResponses(inspectionId, session, useTauri = false) {
 
 export async function saveResponse(
   inspectionId,
-  criterionId,
+  criterionKey,
   conforme,
   observation,
   session,
@@ -48,7 +48,7 @@ export async function saveResponse(
     'cmd_save_response',
     {
       inspectionId,
-      criterionId,
+      criterionKey,
       conforme,
       observation,
       token: session?.token,
@@ -204,7 +204,7 @@ describe('API - Fallback Database', () => {
       fallbackInvoke('cmd_save_response', {
         token: session.token,
         inspectionId: id,
-        criterionId: 1,
+        criterionKey: '1.01',
         conforme: true,
         observation: 'OK',
         session
@@ -147,10 +147,10 @@ describe('Inspections', () => {
     });
 
     it('should return saved responses', async () => {
-      await saveResponse(inspectionId, 1, true, 'OK', session);
+      await saveResponse(inspectionId, '1.01', true, 'OK', session);
       const responses = await getResponses(inspectionId, session);
       expect(responses.length).toBe(1);
-      expect(responses[0].criterion_id).toBe(1);
+      expect(responses[0].criterion_key).toBe('1.01');
       expect(responses[0].conforme).toBe(true);
     });
   });
@@ -169,17 +169,17 @@ describe('Inspections', () => {
     });
 
     it('should save conforme response', async () => {
-      await saveResponse(inspectionId, 1, true, 'Observation', session);
+      await saveResponse(inspectionId, '1.01', true, 'Observation', session);
       const responses = await getResponses(inspectionId, session);
-      const response = responses.find(r => r.criterion_id === 1);
+      const response = responses.find(r => r.criterion_key === '1.01');
       expect(response.conforme).toBe(true);
       expect(response.observation).toBe('Observation');
     });
 
     it('should save non-conforme response', async () => {
-      await saveResponse(inspectionId, 1, false, 'Non conforme', session);
+      await saveResponse(inspectionId, '1.01', false, 'Non conforme', session);
       const responses = await getResponses(inspectionId, session);
-      const response = responses.find(r => r.criterion_id === 1);
+      const response = responses.find(r => r.criterion_key === '1.01');
       expect(response.conforme).toBe(false);
     });
 
@@ -187,16 +187,16 @@ describe('Inspections', () => {
       let insp = await getInspection(inspectionId, session);
       expect(insp.status).toBe('draft');
 
-      await saveResponse(inspectionId, 1, true, '', session);
+      await saveResponse(inspectionId, '1.01', true, '', session);
       insp = await getInspection(inspectionId, session);
       expect(insp.status).toBe('in_progress');
     });
 
     it('should update existing response', async () => {
-      await saveResponse(inspectionId, 1, true, 'First', session);
-      await saveResponse(inspectionId, 1, false, 'Updated', session);
+      await saveResponse(inspectionId, '1.01', true, 'First', session);
+      await saveResponse(inspectionId, '1.01', false, 'Updated', session);
       const responses = await getResponses(inspectionId, session);
-      const response = responses.find(r => r.criterion_id === 1);
+      const response = responses.find(r => r.criterion_key === '1.01');
       expect(response.conforme).toBe(false);
       expect(response.observation).toBe('Updated');
     });
@@ -256,7 +256,7 @@ describe('Inspections', () => {
     });
 
     it('should delete responses', async () => {
-      await saveResponse(inspectionId, 1, true, '', session);
+      await saveResponse(inspectionId, '1.01', true, '', session);
       await deleteInspection(inspectionId, session);
       expect(DB.responses[inspectionId]).toBeUndefined();
     });